@@ -0,0 +1,557 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{EdgeId, Graph, NodeId};
+
+fn adjacency(graph: &Graph) -> Vec<Vec<NodeId>> {
+    (0..graph.node_count())
+        .map(|id| graph.out_neighbors(id).collect())
+        .collect()
+}
+
+/// Which edges to follow when collecting a neighborhood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Follow edges from source to target.
+    Down,
+    /// Follow edges from target to source.
+    Up,
+    /// Union of [`Direction::Down`] and [`Direction::Up`].
+    Both,
+}
+
+/// Returns the nodes reachable from (`Down`) or reaching (`Up`) any of
+/// `roots` within `depth` edges, including the roots themselves. `None`
+/// means unlimited depth.
+pub fn neighborhood(
+    graph: &Graph,
+    roots: &[NodeId],
+    depth: Option<usize>,
+    direction: Direction,
+) -> HashSet<NodeId> {
+    if direction == Direction::Both {
+        let mut nodes = neighborhood(graph, roots, depth, Direction::Down);
+        nodes.extend(neighborhood(graph, roots, depth, Direction::Up));
+        return nodes;
+    }
+
+    let mut visited = roots.iter().copied().collect::<HashSet<_>>();
+    let mut queue = roots.iter().map(|&id| (id, 0)).collect::<VecDeque<_>>();
+    while let Some((node, distance)) = queue.pop_front() {
+        if depth.is_some_and(|depth| distance >= depth) {
+            continue;
+        }
+        let next: Vec<NodeId> = match direction {
+            Direction::Down => graph.out_neighbors(node).collect(),
+            _ => graph.in_neighbors(node).collect(),
+        };
+        for next in next {
+            if visited.insert(next) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    visited
+}
+
+/// Returns the strongly connected components of `graph` (Tarjan's algorithm).
+///
+/// Components are returned in reverse topological order, i.e. a component is
+/// listed before every component that depends on it. Node ids inside a
+/// component are sorted.
+pub fn strongly_connected_components(graph: &Graph) -> Vec<Vec<NodeId>> {
+    tarjan(&adjacency(graph))
+}
+
+fn tarjan(adjacency: &[Vec<NodeId>]) -> Vec<Vec<NodeId>> {
+    const UNVISITED: usize = usize::MAX;
+
+    let n = adjacency.len();
+
+    let mut index = vec![UNVISITED; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut components = Vec::new();
+
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        // explicit call stack of (node, position of the next successor to visit)
+        let mut call_stack = vec![(root, 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&(node, pos)) = call_stack.last() {
+            if let Some(&next) = adjacency[node].get(pos) {
+                call_stack.last_mut().unwrap().1 += 1;
+                if index[next] == UNVISITED {
+                    index[next] = next_index;
+                    lowlink[next] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    call_stack.push((next, 0));
+                } else if on_stack[next] {
+                    lowlink[node] = lowlink[node].min(index[next]);
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some(&(parent, _)) = call_stack.last() {
+                lowlink[parent] = lowlink[parent].min(lowlink[node]);
+            }
+            if lowlink[node] == index[node] {
+                let mut component = Vec::new();
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Returns up to `limit` elementary cycles of `graph` (Johnson's algorithm).
+///
+/// Each cycle is the list of nodes in path order, starting at its smallest
+/// node id; the closing edge back to the first node is implied. A self-loop
+/// is reported as a single-node cycle, and parallel edges do not count as
+/// separate cycles. Cycles are ordered by their first node.
+pub fn cycles(graph: &Graph, limit: usize) -> Vec<Vec<NodeId>> {
+    let adjacency = adjacency(graph)
+        .into_iter()
+        .map(|successors| successors.into_iter().unique().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut in_cycle = vec![false; adjacency.len()];
+    for component in tarjan(&adjacency) {
+        if is_cyclic(&component, &adjacency) {
+            component.iter().for_each(|&node| in_cycle[node] = true);
+        }
+    }
+
+    let mut cycles = Vec::new();
+    for start in (0..adjacency.len()).filter(|&node| in_cycle[node]) {
+        if cycles.len() >= limit {
+            break;
+        }
+
+        // the component of `start` among the nodes not smaller than it
+        let sub = adjacency
+            .iter()
+            .enumerate()
+            .map(|(node, successors)| match node < start {
+                true => Vec::new(),
+                false => successors.iter().copied().filter(|&n| n >= start).collect(),
+            })
+            .collect::<Vec<_>>();
+        let Some(component) = tarjan(&sub)
+            .into_iter()
+            .find(|component| component.contains(&start))
+        else {
+            continue;
+        };
+        if !is_cyclic(&component, &sub) {
+            continue;
+        }
+        let members = component.into_iter().collect::<HashSet<_>>();
+        let sub = sub
+            .into_iter()
+            .enumerate()
+            .map(|(node, successors)| match members.contains(&node) {
+                true => successors
+                    .into_iter()
+                    .filter(|n| members.contains(n))
+                    .collect(),
+                false => Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        circuits(&sub, start, limit, &mut cycles);
+    }
+    cycles
+}
+
+// the cycles through `start`, added to `cycles` until there are `limit`
+fn circuits(adjacency: &[Vec<NodeId>], start: NodeId, limit: usize, cycles: &mut Vec<Vec<NodeId>>) {
+    let mut blocked = vec![false; adjacency.len()];
+    // nodes to unblock when the key is unblocked
+    let mut blocked_by = vec![HashSet::new(); adjacency.len()];
+    let mut path = vec![start];
+    // (node, position of the next successor to visit, whether a cycle was found)
+    let mut call_stack = vec![(start, 0, false)];
+    blocked[start] = true;
+
+    while let Some(&(node, pos, found)) = call_stack.last() {
+        if let Some(&next) = adjacency[node].get(pos) {
+            call_stack.last_mut().unwrap().1 += 1;
+            if next == start {
+                cycles.push(path.clone());
+                call_stack.last_mut().unwrap().2 = true;
+                if cycles.len() >= limit {
+                    return;
+                }
+            } else if !blocked[next] {
+                blocked[next] = true;
+                path.push(next);
+                call_stack.push((next, 0, false));
+            }
+            continue;
+        }
+
+        call_stack.pop();
+        path.pop();
+        if found {
+            let mut unblock = vec![node];
+            while let Some(node) = unblock.pop() {
+                if blocked[node] {
+                    blocked[node] = false;
+                    unblock.extend(blocked_by[node].drain());
+                }
+            }
+        } else {
+            for &next in adjacency[node].iter() {
+                blocked_by[next].insert(node);
+            }
+        }
+        if let Some(parent) = call_stack.last_mut() {
+            parent.2 |= found;
+        }
+    }
+}
+
+fn is_cyclic(component: &[NodeId], adjacency: &[Vec<NodeId>]) -> bool {
+    component.len() > 1 || adjacency[component[0]].contains(&component[0])
+}
+
+/// Returns the strongly connected components of `graph` that contain a cycle.
+pub fn cyclic_components(graph: &Graph) -> Vec<Vec<NodeId>> {
+    let adjacency = adjacency(graph);
+    strongly_connected_components(graph)
+        .into_iter()
+        .filter(|component| is_cyclic(component, &adjacency))
+        .collect()
+}
+
+/// Returns a topological ordering of `graph` grouped by level.
+///
+/// Every node appears after all nodes that have an edge to it; nodes on the
+/// same level do not depend on each other. Node ids inside a level are
+/// sorted. When the graph is not acyclic, the cyclic strongly connected
+/// components are returned as the error.
+pub fn topological_levels(graph: &Graph) -> Result<Vec<Vec<NodeId>>, Vec<Vec<NodeId>>> {
+    let components = cyclic_components(graph);
+    if !components.is_empty() {
+        return Err(components);
+    }
+
+    let adjacency = adjacency(graph);
+    let mut in_degree = vec![0; adjacency.len()];
+    for successors in adjacency.iter() {
+        for &next in successors {
+            in_degree[next] += 1;
+        }
+    }
+
+    let mut levels = Vec::new();
+    let mut level = (0..adjacency.len())
+        .filter(|&node| in_degree[node] == 0)
+        .collect::<Vec<_>>();
+    while !level.is_empty() {
+        let mut next_level = Vec::new();
+        for &node in level.iter() {
+            for &next in adjacency[node].iter() {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    next_level.push(next);
+                }
+            }
+        }
+        next_level.sort_unstable();
+        levels.push(level);
+        level = next_level;
+    }
+    Ok(levels)
+}
+
+/// Returns a shortest path from `from` to `to`, including both ends.
+pub fn shortest_path(graph: &Graph, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+    let mut parent = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    parent.insert(from, from);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            let mut path = vec![to];
+            let mut cur = to;
+            while cur != from {
+                cur = parent[&cur];
+                path.push(cur);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in graph.out_neighbors(node) {
+            if let Entry::Vacant(entry) = parent.entry(next) {
+                entry.insert(node);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Returns up to `limit` simple paths from `from` to `to`, shortest first.
+///
+/// Paths are enumerated depth first, so when the limit is hit the result is
+/// not necessarily the `limit` shortest paths of the graph.
+pub fn simple_paths(graph: &Graph, from: NodeId, to: NodeId, limit: usize) -> Vec<Vec<NodeId>> {
+    let mut paths = Vec::new();
+    let mut path = vec![from];
+    let mut on_path = HashSet::from([from]);
+    // successors of each node on `path` that are still to be visited
+    let mut pending = vec![unvisited_successors(graph, from)];
+
+    while paths.len() < limit {
+        let Some(successors) = pending.last_mut() else {
+            break;
+        };
+        let Some(next) = successors.pop() else {
+            pending.pop();
+            on_path.remove(&path.pop().unwrap());
+            continue;
+        };
+        if next == to {
+            let mut found = path.clone();
+            found.push(to);
+            paths.push(found);
+        } else if on_path.insert(next) {
+            path.push(next);
+            pending.push(unvisited_successors(graph, next));
+        }
+    }
+
+    paths.sort_by_key(|path| path.len());
+    paths
+}
+
+// distinct successors of `node`, reversed so that popping visits them in edge order
+fn unvisited_successors(graph: &Graph, node: NodeId) -> Vec<NodeId> {
+    let mut successors = graph.out_neighbors(node).unique().collect::<Vec<_>>();
+    successors.reverse();
+    successors
+}
+
+/// Returns the edges of `graph` that are implied by a longer path.
+///
+/// The reduction is computed on the condensation of the graph: an edge is
+/// implied when its endpoints' components are also connected through another
+/// component. Edges inside a strongly connected component, and edges between
+/// two components that are not otherwise connected, are always kept, even
+/// when several of them connect the same two components. Parallel edges with
+/// the same endpoints are not considered redundant.
+pub fn transitive_reduction(graph: &Graph) -> Vec<EdgeId> {
+    let components = strongly_connected_components(graph);
+    let mut component_of = vec![0; graph.node_count()];
+    for (idx, component) in components.iter().enumerate() {
+        for &node in component {
+            component_of[node] = idx;
+        }
+    }
+
+    let mut successors = vec![Vec::new(); components.len()];
+    for edge in graph.edges() {
+        let (from, to) = (component_of[edge.from], component_of[edge.to]);
+        if from != to {
+            successors[from].push(to);
+        }
+    }
+
+    // components come in reverse topological order, so every successor of a
+    // component has a smaller index and its reachable set is already known
+    let mut reachable: Vec<BitSet> = Vec::with_capacity(components.len());
+    let mut indirect: Vec<BitSet> = Vec::with_capacity(components.len());
+    for successors in successors.iter() {
+        let mut reach = BitSet::new(components.len());
+        let mut through = BitSet::new(components.len());
+        for &next in successors.iter() {
+            reach.insert(next);
+            reach.union_with(&reachable[next]);
+            through.union_with(&reachable[next]);
+        }
+        reachable.push(reach);
+        indirect.push(through);
+    }
+
+    graph
+        .edges()
+        .enumerate()
+        .filter(|(_, edge)| {
+            let (from, to) = (component_of[edge.from], component_of[edge.to]);
+            from != to && indirect[from].contains(to)
+        })
+        .map(|(id, _)| id)
+        .collect()
+}
+
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        BitSet {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, idx: usize) {
+        self.words[idx / 64] |= 1 << (idx % 64);
+    }
+
+    fn contains(&self, idx: usize) -> bool {
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    fn union_with(&mut self, other: &BitSet) {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Edge;
+
+    fn build(edges: &[(&str, &str)]) -> Graph {
+        let mut graph = Graph::new();
+        for (from, to) in edges {
+            let from = graph.insert_node(from);
+            let to = graph.insert_node(to);
+            graph.add_edge(Edge {
+                from,
+                to,
+                label: None,
+                attributes: Default::default(),
+            });
+        }
+        graph
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph = build(&[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("d", "e")]);
+        assert_eq!(
+            strongly_connected_components(&graph),
+            vec![vec![4], vec![3], vec![0, 1, 2]]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let graph = build(&[("a", "b"), ("b", "c"), ("c", "d"), ("d", "a")]);
+        assert_eq!(cycles(&graph, 10), vec![vec![0, 1, 2, 3]]);
+
+        let graph = build(&[("a", "b"), ("b", "b"), ("b", "c")]);
+        assert_eq!(cycles(&graph, 10), vec![vec![1]]);
+
+        // every cycle of a component, not just one
+        let graph = build(&[
+            ("a", "b"),
+            ("b", "a"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "b"),
+            ("c", "c"),
+            ("a", "b"),
+        ]);
+        assert_eq!(
+            cycles(&graph, 10),
+            vec![vec![0, 1], vec![0, 1, 2], vec![1, 2], vec![2]]
+        );
+        assert_eq!(cycles(&graph, 2).len(), 2);
+
+        let graph = build(&[("a", "b"), ("b", "c"), ("a", "c")]);
+        assert!(cycles(&graph, 10).is_empty());
+    }
+
+    #[test]
+    fn test_topological_levels() {
+        let graph = build(&[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("a", "d")]);
+        assert_eq!(
+            topological_levels(&graph),
+            Ok(vec![vec![0], vec![1, 2], vec![3]])
+        );
+
+        let graph = build(&[("a", "b"), ("b", "c"), ("c", "b"), ("c", "d")]);
+        assert_eq!(topological_levels(&graph), Err(vec![vec![1, 2]]));
+    }
+
+    #[test]
+    fn test_neighborhood() {
+        let graph = build(&[("a", "b"), ("b", "c"), ("c", "d"), ("e", "b")]);
+        let sorted = |nodes: HashSet<NodeId>| nodes.into_iter().sorted().collect::<Vec<_>>();
+
+        let nodes = neighborhood(&graph, &[1], None, Direction::Down);
+        assert_eq!(sorted(nodes), vec![1, 2, 3]);
+        let nodes = neighborhood(&graph, &[1], Some(1), Direction::Down);
+        assert_eq!(sorted(nodes), vec![1, 2]);
+        let nodes = neighborhood(&graph, &[2], None, Direction::Up);
+        assert_eq!(sorted(nodes), vec![0, 1, 2, 4]);
+        let nodes = neighborhood(&graph, &[2], Some(1), Direction::Both);
+        assert_eq!(sorted(nodes), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_paths() {
+        let graph = build(&[
+            ("a", "b"),
+            ("b", "d"),
+            ("a", "c"),
+            ("c", "e"),
+            ("e", "d"),
+            ("d", "a"),
+        ]);
+        assert_eq!(shortest_path(&graph, 0, 2), Some(vec![0, 1, 2]));
+        assert_eq!(shortest_path(&graph, 0, 0), Some(vec![0]));
+        assert_eq!(
+            simple_paths(&graph, 0, 2, 10),
+            vec![vec![0, 1, 2], vec![0, 3, 4, 2]]
+        );
+        assert_eq!(simple_paths(&graph, 0, 2, 1).len(), 1);
+
+        let graph = build(&[("a", "b"), ("c", "b")]);
+        assert_eq!(shortest_path(&graph, 0, 2), None);
+        assert!(simple_paths(&graph, 0, 2, 10).is_empty());
+    }
+
+    #[test]
+    fn test_transitive_reduction() {
+        let graph = build(&[("a", "b"), ("b", "c"), ("a", "c"), ("c", "d"), ("a", "d")]);
+        assert_eq!(transitive_reduction(&graph), vec![2, 4]);
+
+        // edges inside a cycle are kept, and so is every direct edge into it
+        let graph = build(&[("a", "b"), ("b", "c"), ("c", "b"), ("a", "c"), ("c", "d")]);
+        assert!(transitive_reduction(&graph).is_empty());
+
+        // an edge into a cycle that is implied through another node is reduced
+        let graph = build(&[("a", "x"), ("x", "b"), ("b", "c"), ("c", "b"), ("a", "c")]);
+        assert_eq!(transitive_reduction(&graph), vec![4]);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Edge, Graph};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Removed,
+    Unchanged,
+}
+
+/// Union of two graphs with the change of every node and edge.
+pub struct Diff {
+    pub graph: Graph,
+    /// indexed by the node ids of `graph`
+    pub nodes: Vec<Change>,
+    /// indexed by the edge ids of `graph`
+    pub edges: Vec<Change>,
+}
+
+/// Compares `old` with `new`, matching nodes by name and edges by their
+/// endpoints and label. Parallel edges with the same label count once.
+///
+/// The merged graph lists the nodes and edges of `old` first, followed by
+/// the ones only present in `new`. Attributes are taken from `new` where an
+/// item exists in both graphs.
+pub fn diff(old: &Graph, new: &Graph) -> Diff {
+    let mut graph = Graph::new();
+    let mut nodes = Vec::new();
+
+    for (id, name) in old.nodes() {
+        let merged = graph.insert_node(name);
+        nodes.push(match new.node_id(name) {
+            Some(new_id) => {
+                *graph.node_attributes_mut(merged) = new.node_attributes(new_id).clone();
+                Change::Unchanged
+            }
+            None => {
+                *graph.node_attributes_mut(merged) = old.node_attributes(id).clone();
+                Change::Removed
+            }
+        });
+    }
+    for (id, name) in new.nodes() {
+        if old.node_id(name).is_none() {
+            let merged = graph.insert_node(name);
+            *graph.node_attributes_mut(merged) = new.node_attributes(id).clone();
+            nodes.push(Change::Added);
+        }
+    }
+
+    let key = |graph: &Graph, edge: &Edge| {
+        (
+            graph.node_name(edge.from).unwrap().to_string(),
+            graph.node_name(edge.to).unwrap().to_string(),
+            edge.label.clone(),
+        )
+    };
+    let mut new_edges = HashMap::new();
+    for edge in new.edges() {
+        new_edges.entry(key(new, edge)).or_insert(edge);
+    }
+
+    let mut seen = HashSet::new();
+    let mut edges = Vec::new();
+    let mut add =
+        |graph: &mut Graph, key: (String, String, Option<String>), edge: &Edge, change| {
+            if !seen.insert(key.clone()) {
+                return;
+            }
+            let (from, to, label) = key;
+            graph.add_edge(Edge {
+                from: graph.node_id(&from).unwrap(),
+                to: graph.node_id(&to).unwrap(),
+                label,
+                attributes: edge.attributes.clone(),
+            });
+            edges.push(change);
+        };
+    for edge in old.edges() {
+        let key = key(old, edge);
+        match new_edges.get(&key) {
+            Some(new_edge) => add(&mut graph, key, new_edge, Change::Unchanged),
+            None => add(&mut graph, key, edge, Change::Removed),
+        }
+    }
+    for edge in new.edges() {
+        add(&mut graph, key(new, edge), edge, Change::Added);
+    }
+
+    Diff {
+        graph,
+        nodes,
+        edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attributes;
+
+    fn build(edges: &[(&str, &str)]) -> Graph {
+        let mut graph = Graph::new();
+        for (from, to) in edges {
+            let from = graph.insert_node(from);
+            let to = graph.insert_node(to);
+            graph.add_edge(Edge {
+                from,
+                to,
+                label: None,
+                attributes: Attributes::new(),
+            });
+        }
+        graph
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = build(&[("a", "b"), ("b", "c"), ("a", "b")]);
+        let new = build(&[("a", "b"), ("a", "d")]);
+        let diff = diff(&old, &new);
+
+        let names = diff.graph.nodes().map(|(_, name)| name).collect::<Vec<_>>();
+        assert_eq!(names, vec!["a", "b", "c", "d"]);
+        assert_eq!(
+            diff.nodes,
+            vec![
+                Change::Unchanged,
+                Change::Unchanged,
+                Change::Removed,
+                Change::Added
+            ]
+        );
+
+        let edges = diff
+            .graph
+            .edges()
+            .map(|edge| (edge.from, edge.to))
+            .collect::<Vec<_>>();
+        assert_eq!(edges, vec![(0, 1), (1, 2), (0, 3)]);
+        assert_eq!(
+            diff.edges,
+            vec![Change::Unchanged, Change::Removed, Change::Added]
+        );
+    }
+}
@@ -11,27 +11,86 @@ pub fn write(
     graph: &crate::Graph,
     file: &mut File,
 ) -> io::Result<()> {
-    writeln!(file, "digraph {} {{", graph_config.name)?;
+    writeln!(file, "digraph {} {{", id(&graph_config.name))?;
     graph_config.write(file)?;
 
     for node in graph.node_arena.nodes.iter().enumerate() {
-        writeln!(file, "  N_{} [label={}];", node.0, node.1)?;
+        write!(file, "  N_{}", node.0)?;
+        write_attributes(file, Some(node.1), &graph.node_attributes[node.0])?;
+        writeln!(file, ";")?;
     }
     for edge in graph.edges.iter() {
-        if let Some(label) = &edge.label {
-            writeln!(
-                file,
-                "  N_{} -> N_{} [label={}];",
-                edge.from, edge.to, label
-            )?;
-        } else {
-            writeln!(file, "  N_{} -> N_{};", edge.from, edge.to)?;
-        }
+        write!(file, "  N_{} -> N_{}", edge.from, edge.to)?;
+        write_attributes(file, edge.label.as_deref(), &edge.attributes)?;
+        writeln!(file, ";")?;
     }
     writeln!(file, "}}")?;
     Ok(())
 }
 
+fn write_attributes(
+    file: &mut File,
+    label: Option<&str>,
+    attributes: &crate::Attributes,
+) -> io::Result<()> {
+    if label.is_none() && attributes.is_empty() {
+        return Ok(());
+    }
+
+    let mut list = Vec::new();
+    if let Some(label) = label.filter(|_| !attributes.contains_key("label")) {
+        list.push(format!("label={}", quote(label)));
+    }
+    for (key, value) in attributes.iter() {
+        list.push(format!("{}={}", id(key), quote(value)));
+    }
+    write!(file, " [{}]", list.join(", "))
+}
+
+const KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+
+/// Returns `s` as a DOT ID: unchanged if it is a plain identifier or numeral,
+/// quoted with [`quote`] otherwise.
+pub fn id(s: &str) -> String {
+    let is_identifier = s
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || !c.is_ascii())
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii());
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let is_numeral = digits.chars().any(|c| c.is_ascii_digit())
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.matches('.').count() <= 1;
+    let is_keyword = KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(s));
+
+    if (is_identifier || is_numeral) && !is_keyword {
+        s.to_string()
+    } else {
+        quote(s)
+    }
+}
+
+/// Returns `s` as a double-quoted DOT string.
+///
+/// Backslashes are doubled so graphviz does not read them as label escapes
+/// like `\N`, and line breaks become `\n`.
+pub fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
 pub fn compile(output_file: &str, filename: &std::path::Path) -> anyhow::Result<()> {
     let extension = fileutil::get_extension(output_file).unwrap_or(DEFAULT_OUTPUT_FORMAT);
     // dot -T${extension} -o ${args.output} ${filename}
@@ -55,3 +114,67 @@ pub fn compile(output_file: &str, filename: &std::path::Path) -> anyhow::Result<
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // inverse of `quote`, following the DOT lexer and graphviz label escapes
+    fn unquote(s: &str) -> String {
+        let inner = s.strip_prefix('"').unwrap().strip_suffix('"').unwrap();
+        let mut unquoted = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c == '"' {
+                panic!("unescaped quote in {}", s);
+            }
+            if c != '\\' {
+                unquoted.push(c);
+                continue;
+            }
+            match chars.next().unwrap() {
+                'n' => unquoted.push('\n'),
+                c => unquoted.push(c),
+            }
+        }
+        unquoted
+    }
+
+    #[test]
+    fn test_quote_round_trip() {
+        let names = [
+            "foo-bar",
+            "my lib",
+            "serde@1.0",
+            "@scope/pkg",
+            "a.b.c",
+            "quote\"inside",
+            "bracket]; N_0 -> N_1 [color=red",
+            "back\\slash\\N",
+            "trailing\\",
+            "line\nbreak",
+            "日本語-パッケージ",
+            "emoji 🦀",
+            "",
+        ];
+        for name in names {
+            assert_eq!(unquote(&quote(name)), name);
+        }
+    }
+
+    #[test]
+    fn test_id() {
+        assert_eq!(id("G"), "G");
+        assert_eq!(id("_a1"), "_a1");
+        assert_eq!(id("12"), "12");
+        assert_eq!(id("-1.5"), "-1.5");
+        assert_eq!(id("日本"), "日本");
+        assert_eq!(id("foo-bar"), "\"foo-bar\"");
+        assert_eq!(id("1a"), "\"1a\"");
+        assert_eq!(id("."), "\".\"");
+        assert_eq!(id("graph"), "\"graph\"");
+        assert_eq!(id("Node"), "\"Node\"");
+        assert_eq!(id(""), "\"\"");
+        assert_eq!(id("a b"), "\"a b\"");
+    }
+}
@@ -1,5 +1,5 @@
-use std::fs::File;
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
 use std::{io, mem};
 
 pub fn get_extension(filename: &str) -> Option<&str> {
@@ -16,3 +16,31 @@ pub fn create_temp_file() -> io::Result<(PathBuf, File)> {
     mem::forget(dir);
     Ok((filename, file))
 }
+
+/// Returns the files below `root` with one of the given extensions, sorted.
+///
+/// Hidden directories and `node_modules` are skipped.
+pub fn source_files(root: &Path, extensions: &[&str]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if entry.file_type()?.is_dir() {
+                if !name.starts_with('.') && name != "node_modules" {
+                    dirs.push(path);
+                }
+            } else if path
+                .extension()
+                .is_some_and(|ext| extensions.iter().any(|e| ext == *e))
+            {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
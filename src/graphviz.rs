@@ -1,5 +1,7 @@
 use std::io::{self, Write};
 
+use crate::dot::quote;
+
 pub struct Config {
     pub name: String,
 
@@ -46,8 +48,14 @@ impl GraphConfig {
     pub fn write(&self, file: &mut dyn Write) -> io::Result<()> {
         let indent = "  ";
         writeln!(file, "{}graph [", indent)?;
-        writeln!(file, "{}{}charset=\"{}\";", indent, indent, self.charset)?;
-        writeln!(file, "{}{}layout={};", indent, indent, self.layout)?;
+        writeln!(
+            file,
+            "{}{}charset={};",
+            indent,
+            indent,
+            quote(&self.charset)
+        )?;
+        writeln!(file, "{}{}layout={};", indent, indent, quote(&self.layout))?;
         writeln!(file, "{}]", indent)?;
         Ok(())
     }
@@ -69,7 +77,7 @@ impl NodeConfig {
     pub fn write(&self, file: &mut dyn Write) -> io::Result<()> {
         let indent = "  ";
         writeln!(file, "{}node [", indent)?;
-        writeln!(file, "{}{}shape=\"{}\";", indent, indent, self.shape)?;
+        writeln!(file, "{}{}shape={};", indent, indent, quote(&self.shape))?;
         writeln!(file, "{}]", indent)?;
         Ok(())
     }
@@ -93,8 +101,10 @@ impl EdgeConfig {
         writeln!(file, "{}edge [", indent)?;
         writeln!(
             file,
-            "{}{}arrowhead=\"{}\";",
-            indent, indent, self.arrowhead
+            "{}{}arrowhead={};",
+            indent,
+            indent,
+            quote(&self.arrowhead)
         )?;
         writeln!(file, "{}]", indent)?;
         Ok(())
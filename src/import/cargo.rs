@@ -0,0 +1,246 @@
+//! `Cargo.lock` and `cargo metadata --format-version 1` importers.
+//!
+//! Packages become `name@version` nodes; edges point from a package to its
+//! dependencies.
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::Graph;
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockPackage>,
+}
+
+#[derive(Deserialize)]
+struct LockPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// Parses a `Cargo.lock` file.
+///
+/// The lockfile does not record dependency kinds, so edges carry no label.
+pub fn parse_lock(input: &str) -> anyhow::Result<Graph> {
+    let lockfile: Lockfile = toml::from_str(input).context("invalid Cargo.lock")?;
+
+    let mut by_name: HashMap<&str, Vec<&LockPackage>> = HashMap::new();
+    for package in lockfile.package.iter() {
+        by_name.entry(&package.name).or_default().push(package);
+    }
+
+    let mut graph = Graph::new();
+    for package in lockfile.package.iter() {
+        let from = format!("{}@{}", package.name, package.version);
+        graph.insert_node(&from);
+
+        for dependency in package.dependencies.iter() {
+            // "name", "name version" or "name version (source)"
+            let mut parts = dependency.splitn(3, ' ');
+            let name = parts.next().unwrap_or_default();
+            let version = parts.next();
+            let source = parts
+                .next()
+                .map(|s| s.trim_start_matches('(').trim_end_matches(')'));
+
+            let candidates = by_name.get(name).map(Vec::as_slice).unwrap_or_default();
+            let dependency = candidates
+                .iter()
+                .find(|p| {
+                    version.is_none_or(|v| p.version == v)
+                        && source.is_none_or(|s| p.source.as_deref() == Some(s))
+                })
+                .with_context(|| {
+                    format!("{} depends on unknown package \"{}\"", from, dependency)
+                })?;
+            let to = format!("{}@{}", dependency.name, dependency.version);
+            super::add_edge(&mut graph, &from, &to, None);
+        }
+    }
+    Ok(graph)
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<MetadataPackage>,
+    resolve: Option<Resolve>,
+}
+
+#[derive(Deserialize)]
+struct MetadataPackage {
+    id: String,
+    name: String,
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct Resolve {
+    nodes: Vec<ResolveNode>,
+}
+
+#[derive(Deserialize)]
+struct ResolveNode {
+    id: String,
+    #[serde(default)]
+    deps: Vec<NodeDep>,
+}
+
+#[derive(Deserialize)]
+struct NodeDep {
+    pkg: String,
+    // missing before cargo 1.41, which only reported normal dependencies
+    #[serde(default)]
+    dep_kinds: Vec<DepKind>,
+}
+
+#[derive(Deserialize)]
+struct DepKind {
+    kind: Option<String>,
+}
+
+/// Parses the JSON printed by `cargo metadata --format-version 1`.
+///
+/// Edges of dev and build dependencies are labelled with their kinds, e.g.
+/// `dev` or `normal, build`; plain normal dependencies have no label.
+pub fn parse_metadata(input: &str) -> anyhow::Result<Graph> {
+    let metadata: Metadata =
+        serde_json::from_str(input).context("invalid cargo metadata output")?;
+    let resolve = metadata
+        .resolve
+        .context("cargo metadata output has no dependency graph (was it run with --no-deps?)")?;
+
+    let mut graph = Graph::new();
+    let mut names = HashMap::new();
+    for package in metadata.packages.iter() {
+        let name = format!("{}@{}", package.name, package.version);
+        graph.insert_node(&name);
+        names.insert(package.id.as_str(), name);
+    }
+    let name = |id: &str| {
+        names
+            .get(id)
+            .with_context(|| format!("unknown package id \"{}\"", id))
+    };
+
+    for node in resolve.nodes.iter() {
+        let from = name(&node.id)?;
+        for dep in node.deps.iter() {
+            let mut kinds = dep
+                .dep_kinds
+                .iter()
+                .map(|k| k.kind.as_deref().unwrap_or("normal"))
+                .collect::<Vec<_>>();
+            kinds.dedup();
+            let label = match kinds.as_slice() {
+                [] | ["normal"] => None,
+                kinds => Some(kinds.join(", ")),
+            };
+            super::add_edge(&mut graph, from, name(&dep.pkg)?, label);
+        }
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_lock() {
+        let input = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "log",
+ "syn 2.0.0",
+]
+
+[[package]]
+name = "log"
+version = "0.4.20"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        let graph = parse_lock(input).unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("app@0.1.0", "log@0.4.20", None),
+                ("app@0.1.0", "syn@2.0.0", None)
+            ]
+        );
+
+        assert!(
+            parse_lock("[[package]]\nname = \"a\"\nversion = \"1\"\ndependencies = [\"b\"]\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let input = r#"{
+  "packages": [
+    {"id": "app 0.1.0 (path+file:///app)", "name": "app", "version": "0.1.0"},
+    {"id": "cc 1.0.0 (registry+x)", "name": "cc", "version": "1.0.0"},
+    {"id": "log 0.4.20 (registry+x)", "name": "log", "version": "0.4.20"}
+  ],
+  "resolve": {
+    "nodes": [
+      {
+        "id": "app 0.1.0 (path+file:///app)",
+        "deps": [
+          {"name": "cc", "pkg": "cc 1.0.0 (registry+x)", "dep_kinds": [{"kind": "build", "target": null}]},
+          {"name": "log", "pkg": "log 0.4.20 (registry+x)", "dep_kinds": [{"kind": null, "target": null}, {"kind": "dev", "target": null}]}
+        ]
+      },
+      {"id": "cc 1.0.0 (registry+x)", "deps": []},
+      {"id": "log 0.4.20 (registry+x)", "deps": []}
+    ],
+    "root": "app 0.1.0 (path+file:///app)"
+  }
+}"#;
+        let graph = parse_metadata(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("app@0.1.0", "cc@1.0.0", Some("build")),
+                ("app@0.1.0", "log@0.4.20", Some("normal, dev"))
+            ]
+        );
+
+        assert!(parse_metadata(r#"{"packages": [], "resolve": null}"#).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! Make-style depfile importer, as written by `gcc -MD`, `rustc
+//! --emit=dep-info` and `ninja -t deps`.
+//!
+//! Every target gets an edge to each of its prerequisites.
+
+use crate::Graph;
+
+/// Parses a depfile.
+///
+/// Handles line continuations, `\ `, `\#` and `$$` escapes, and the
+/// indented prerequisite lists printed by `ninja -t deps`.
+pub fn parse(input: &str) -> anyhow::Result<Graph> {
+    let mut graph = Graph::new();
+    // targets of a `ninja -t deps` record, whose prerequisites follow on
+    // indented lines
+    let mut ninja_targets: Option<Vec<String>> = None;
+
+    for (idx, line) in logical_lines(input) {
+        if let Some(targets) = ninja_targets.as_ref() {
+            if line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+                for prerequisite in words(&line) {
+                    for target in targets.iter() {
+                        super::add_edge(&mut graph, target, &prerequisite, None);
+                    }
+                }
+                continue;
+            }
+            ninja_targets = None;
+        }
+
+        let (line, comment) = split_comment(&line);
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (targets, prerequisites) = split_rule(line).ok_or_else(|| {
+            anyhow::anyhow!(
+                "error parsing line {}: \"{}\" (expected \"target: prerequisites\")",
+                idx + 1,
+                line
+            )
+        })?;
+
+        let targets = words(targets);
+        for target in targets.iter() {
+            graph.insert_node(target);
+        }
+        for prerequisite in words(prerequisites) {
+            for target in targets.iter() {
+                super::add_edge(&mut graph, target, &prerequisite, None);
+            }
+        }
+        if comment.is_some_and(|c| c.starts_with("#deps")) {
+            ninja_targets = Some(targets);
+        }
+    }
+    Ok(graph)
+}
+
+// lines joined at trailing backslashes, with the index of their first line
+fn logical_lines(input: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    for (idx, line) in input.lines().enumerate() {
+        let (start, mut joined) = current.take().unwrap_or((idx, String::new()));
+        match line.strip_suffix('\\') {
+            Some(line) => {
+                joined.push_str(line);
+                joined.push(' ');
+                current = Some((start, joined));
+            }
+            None => {
+                joined.push_str(line);
+                lines.push((start, joined));
+            }
+        }
+    }
+    lines.extend(current);
+    lines
+}
+
+// splits off a comment starting at an unescaped '#'
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            '#' if !escaped => return (&line[..idx], Some(&line[idx..])),
+            '\\' => escaped = !escaped,
+            _ => escaped = false,
+        }
+    }
+    (line, None)
+}
+
+// splits at the first ':' followed by whitespace or the end of the line, so
+// that Windows drive letters like `C:\` stay part of the path
+fn split_rule(line: &str) -> Option<(&str, &str)> {
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            ':' if !escaped => {
+                let rest = &line[idx + 1..];
+                if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+                    return Some((&line[..idx], rest));
+                }
+            }
+            '\\' => {
+                escaped = !escaped;
+                continue;
+            }
+            _ => {}
+        }
+        escaped = false;
+    }
+    None
+}
+
+// whitespace separated words, unescaping `\ `, `\#` and `$$`
+fn words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some(' ') | Some('#')) => {
+                word.extend(chars.next());
+            }
+            '$' if chars.peek() == Some(&'$') => {
+                chars.next();
+                word.push('$');
+            }
+            c if c.is_whitespace() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = "main.o: main.c include/my\\ lib.h \\\n  util.h cost$$.h\n\
+                     \n\
+                     include/my\\ lib.h:\n\
+                     C:\\src\\a.o C:\\src\\b.o: C:\\src\\common.h # comment\n\
+                     # env-dep:CARGO_PKG_NAME=deptree\n";
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("main.o", "main.c"),
+                ("main.o", "include/my lib.h"),
+                ("main.o", "util.h"),
+                ("main.o", "cost$.h"),
+                ("C:\\src\\a.o", "C:\\src\\common.h"),
+                ("C:\\src\\b.o", "C:\\src\\common.h"),
+            ]
+        );
+        assert!(parse("main.o main.c\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_ninja_deps() {
+        let input = "obj/main.o: #deps 2, deps mtime 1700000000 (VALID)\n    \
+                     ../src/main.cc\n    \
+                     ../src/util.h\n\
+                     \n\
+                     obj/util.o: #deps 1, deps mtime 1700000000 (STALE)\n    \
+                     ../src/util.cc\n\
+                     \n";
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("obj/main.o", "../src/main.cc"),
+                ("obj/main.o", "../src/util.h"),
+                ("obj/util.o", "../src/util.cc"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,479 @@
+//! Graphviz DOT importer.
+//!
+//! Node and edge statements of the first graph in the input are loaded,
+//! including those inside subgraphs. Edge `label`s become [`Edge`] labels
+//! and all other attributes are kept as they are. Ports are dropped, and so
+//! are graph attributes and `graph`/`node`/`edge` defaults, which deptree
+//! replaces with its own styling.
+//!
+//! Edges of an undirected `graph` keep their order and get `dir="none"`, like
+//! the undirected edges of GraphML. As in Graphviz, `->` is an error in a
+//! `graph` and `--` is an error in a `digraph`.
+//!
+//! Graphviz escapes like `\n` are only read in labels; IDs keep their
+//! backslashes. HTML-like strings (`<...>`) are rejected, since deptree has no
+//! way to write them back.
+
+use std::fmt;
+
+use crate::{Attributes, Edge, Graph, NodeId};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    // unquoted keyword, stored in lower case
+    Keyword(String),
+    Id(String),
+    // "->" or "--"
+    EdgeOp(&'static str),
+    Punct(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Keyword(s) | Token::Id(s) => write!(f, "\"{}\"", s),
+            Token::EdgeOp(op) => write!(f, "'{}'", op),
+            Token::Punct(c) => write!(f, "'{}'", c),
+        }
+    }
+}
+
+const KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+
+/// Parses a DOT graph.
+pub fn parse(input: &str) -> anyhow::Result<Graph> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        graph: Graph::new(),
+        directed: true,
+    };
+    parser.parse_graph()?;
+    Ok(parser.graph)
+}
+
+fn tokenize(input: &str) -> anyhow::Result<Vec<(Token, usize)>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+    let mut at_line_start = true;
+    while let Some(c) = chars.next() {
+        let start = line;
+        match c {
+            '\n' => {
+                line += 1;
+                at_line_start = true;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            // preprocessor output lines
+            '#' if at_line_start => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => {
+                            line += (c == '\n') as usize;
+                            last = c;
+                        }
+                        None => {
+                            return Err(anyhow::anyhow!("unterminated comment at line {}", start))
+                        }
+                    }
+                }
+            }
+            '-' if matches!(chars.peek(), Some('>') | Some('-')) => {
+                let op = match chars.next() {
+                    Some('>') => "->",
+                    _ => "--",
+                };
+                tokens.push((Token::EdgeOp(op), start));
+            }
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        // other escapes are only meaningful in labels, see `unescape_label`
+                        Some('\\') => match chars.next() {
+                            Some('"') => s.push('"'),
+                            // line continuation
+                            Some('\n') => line += 1,
+                            Some(c) => {
+                                s.push('\\');
+                                s.push(c);
+                            }
+                            None => {
+                                return Err(anyhow::anyhow!(
+                                    "unterminated string at line {}",
+                                    start
+                                ))
+                            }
+                        },
+                        Some(c) => {
+                            line += (c == '\n') as usize;
+                            s.push(c);
+                        }
+                        None => {
+                            return Err(anyhow::anyhow!("unterminated string at line {}", start))
+                        }
+                    }
+                }
+                // "a" + "b" concatenation
+                match tokens.last_mut() {
+                    Some((Token::Punct('+'), _)) => {
+                        tokens.pop();
+                        match tokens.last_mut() {
+                            Some((Token::Id(prev), _)) => prev.push_str(&s),
+                            _ => return Err(anyhow::anyhow!("unexpected '+' at line {}", start)),
+                        }
+                    }
+                    _ => tokens.push((Token::Id(s), start)),
+                }
+            }
+            '<' => {
+                return Err(anyhow::anyhow!(
+                    "HTML-like strings are not supported, found one at line {}",
+                    start
+                ))
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' || !c.is_ascii() => {
+                let mut s = c.to_string();
+                while let Some(c) = chars.next_if(|&c| {
+                    c.is_ascii_alphanumeric() || c == '_' || c == '.' || !c.is_ascii()
+                }) {
+                    s.push(c);
+                }
+                match KEYWORDS.iter().find(|k| k.eq_ignore_ascii_case(&s)) {
+                    Some(k) => tokens.push((Token::Keyword(k.to_string()), start)),
+                    None => tokens.push((Token::Id(s), start)),
+                }
+            }
+            c if "{}[]=;,:+".contains(c) => tokens.push((Token::Punct(c), start)),
+            c => {
+                return Err(anyhow::anyhow!(
+                    "unexpected character '{}' at line {}",
+                    c,
+                    start
+                ))
+            }
+        }
+        at_line_start = false;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    graph: Graph,
+    // false in a `graph`, where edges are written with "--"
+    directed: bool,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> anyhow::Result<Token> {
+        let (token, _) = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| anyhow::anyhow!("unexpected end of input"))?;
+        self.pos += 1;
+        Ok(token.clone())
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        self.pos += found as usize;
+        found
+    }
+
+    fn unexpected(&self) -> anyhow::Error {
+        match self.tokens.get(self.pos) {
+            Some((token, line)) => anyhow::anyhow!("unexpected {} at line {}", token, line),
+            None => anyhow::anyhow!("unexpected end of input"),
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> anyhow::Result<()> {
+        match self.eat(&token) {
+            true => Ok(()),
+            false => Err(self.unexpected()),
+        }
+    }
+
+    fn id(&mut self) -> anyhow::Result<String> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.pos += 1;
+                Ok(id)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    // [strict] (graph | digraph) [ID] '{' stmt_list '}'
+    fn parse_graph(&mut self) -> anyhow::Result<()> {
+        self.eat(&Token::Keyword("strict".to_string()));
+        match self.next()? {
+            Token::Keyword(k) if k == "graph" || k == "digraph" => {
+                self.directed = k == "digraph";
+            }
+            _ => {
+                self.pos -= 1;
+                return Err(self.unexpected());
+            }
+        }
+        if let Some(Token::Id(_)) = self.peek() {
+            self.pos += 1;
+        }
+        self.expect(Token::Punct('{'))?;
+        self.parse_statements()?;
+        Ok(())
+    }
+
+    // statements up to and including the closing '}', returning the nodes
+    // they mention
+    fn parse_statements(&mut self) -> anyhow::Result<Vec<NodeId>> {
+        let mut nodes = Vec::new();
+        while !self.eat(&Token::Punct('}')) {
+            self.parse_statement(&mut nodes)?;
+            self.eat(&Token::Punct(';'));
+        }
+        Ok(nodes)
+    }
+
+    fn parse_statement(&mut self, nodes: &mut Vec<NodeId>) -> anyhow::Result<()> {
+        match self.peek() {
+            Some(Token::Keyword(k)) if k == "graph" || k == "node" || k == "edge" => {
+                self.pos += 1;
+                self.parse_attributes()?;
+                return Ok(());
+            }
+            Some(Token::Id(_))
+                if self.tokens.get(self.pos + 1).map(|(t, _)| t) == Some(&Token::Punct('=')) =>
+            {
+                self.pos += 2;
+                self.id()?;
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let mut endpoints = vec![self.parse_endpoint()?];
+        let op = Token::EdgeOp(if self.directed { "->" } else { "--" });
+        while self.eat(&op) {
+            endpoints.push(self.parse_endpoint()?);
+        }
+        if let Some(Token::EdgeOp(_)) = self.peek() {
+            return Err(self.unexpected());
+        }
+        nodes.extend(endpoints.iter().flatten());
+
+        let mut attributes = self.parse_attributes()?;
+        if endpoints.len() == 1 {
+            // a node statement; a subgraph on its own has no attributes
+            for &node in endpoints[0].iter() {
+                let node_attributes = self.graph.node_attributes_mut(node);
+                node_attributes.extend(attributes.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+            return Ok(());
+        }
+
+        let label = attributes.remove("label");
+        if !self.directed {
+            attributes
+                .entry("dir".to_string())
+                .or_insert_with(|| "none".to_string());
+        }
+        for pair in endpoints.windows(2) {
+            for &from in pair[0].iter() {
+                for &to in pair[1].iter() {
+                    self.graph.add_edge(Edge {
+                        from,
+                        to,
+                        label: label.clone(),
+                        attributes: attributes.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // a node ID with an optional port, or a subgraph
+    fn parse_endpoint(&mut self) -> anyhow::Result<Vec<NodeId>> {
+        let subgraph = Token::Keyword("subgraph".to_string());
+        if self.eat(&subgraph) {
+            if let Some(Token::Id(_)) = self.peek() {
+                self.pos += 1;
+            }
+            self.expect(Token::Punct('{'))?;
+            return self.parse_statements();
+        }
+        if self.eat(&Token::Punct('{')) {
+            return self.parse_statements();
+        }
+
+        let id = self.id()?;
+        // port and compass point
+        for _ in 0..2 {
+            if self.eat(&Token::Punct(':')) {
+                self.id()?;
+            }
+        }
+        Ok(vec![self.graph.insert_node(&id)])
+    }
+
+    // ('[' (ID ['=' ID] [';' | ','])* ']')*
+    fn parse_attributes(&mut self) -> anyhow::Result<Attributes> {
+        let mut attributes = Attributes::new();
+        while self.eat(&Token::Punct('[')) {
+            while !self.eat(&Token::Punct(']')) {
+                let key = self.id()?;
+                let value = match self.eat(&Token::Punct('=')) {
+                    true if LABELS.contains(&key.as_str()) => unescape_label(&self.id()?),
+                    true => self.id()?,
+                    false => "true".to_string(),
+                };
+                attributes.insert(key, value);
+                if !self.eat(&Token::Punct(',')) {
+                    self.eat(&Token::Punct(';'));
+                }
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+const LABELS: [&str; 4] = ["label", "xlabel", "headlabel", "taillabel"];
+
+// graphviz label escapes: line breaks (centered, left and right justified)
+// and backslashes; `\N` and the like are kept
+fn unescape_label(label: &str) -> String {
+    let mut unescaped = String::with_capacity(label.len());
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('l') | Some('r') => unescaped.push('\n'),
+            Some('\\') => unescaped.push('\\'),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = r#"
+            /* generated */
+            strict digraph "deps" {
+                rankdir = LR
+                node [shape=box];
+                a -> b -> c [label=x, color="red"];
+                "my lib" [label="My\nLib", style=filled]
+                "my lib":p:n -> a // trailing comment
+                subgraph cluster_0 { d; e } -> c
+                "con" + "cat" -> { f g }
+                "C:\new" -> "C:\\old" [label="C:\\new"]
+            }
+        "#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("a", "b", Some("x")),
+                ("b", "c", Some("x")),
+                ("my lib", "a", None),
+                ("d", "c", None),
+                ("e", "c", None),
+                ("concat", "f", None),
+                ("concat", "g", None),
+                ("C:\\new", "C:\\\\old", Some("C:\\new")),
+            ]
+        );
+        assert_eq!(
+            graph
+                .edge(0)
+                .unwrap()
+                .attributes
+                .get("color")
+                .map(String::as_str),
+            Some("red")
+        );
+        let lib = graph.node_id("my lib").unwrap();
+        assert_eq!(
+            graph.node_attributes(lib).get("label").map(String::as_str),
+            Some("My\nLib")
+        );
+        assert_eq!(
+            graph.node_attributes(lib).get("style").map(String::as_str),
+            Some("filled")
+        );
+        assert!(graph
+            .node_attributes(graph.node_id("a").unwrap())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_undirected() {
+        let graph = parse("graph { a -- b; b -- c [dir=back] }").unwrap();
+        assert_eq!(edges(&graph), vec![("a", "b", None), ("b", "c", None)]);
+        assert_eq!(graph.edge(0).unwrap().attributes["dir"], "none");
+        assert_eq!(graph.edge(1).unwrap().attributes["dir"], "back");
+
+        let graph = parse("digraph { a -> b }").unwrap();
+        assert!(!graph.edge(0).unwrap().attributes.contains_key("dir"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("digraph {\n  a -> ;\n}").err().unwrap();
+        assert_eq!(err.to_string(), "unexpected ';' at line 2");
+        assert!(parse("digraph { a -> \"b }").is_err());
+        let err = parse("graph {\n  a -> b\n}").err().unwrap();
+        assert_eq!(err.to_string(), "unexpected '->' at line 2");
+        let err = parse("digraph { a -> b -- c }").err().unwrap();
+        assert_eq!(err.to_string(), "unexpected '--' at line 1");
+
+        let err = parse("digraph { a -> \"b\\").err().unwrap();
+        assert_eq!(err.to_string(), "unterminated string at line 1");
+        let err = parse("digraph {\n  a [label=<<b>A</b>>]\n}").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "HTML-like strings are not supported, found one at line 2"
+        );
+    }
+}
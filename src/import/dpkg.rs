@@ -0,0 +1,266 @@
+//! dpkg importer, for `/var/lib/dpkg/status` and `debian/control`.
+//!
+//! Every package paragraph becomes a node, and its `Pre-Depends`, `Depends`,
+//! `Recommends` and `Suggests` become edges labelled with the field and the
+//! version constraint, e.g. `Depends (>= 2.36)`. Architecture qualifiers,
+//! architecture restrictions, build profiles and `${substvars}` are dropped.
+//!
+//! Alternatives like `default-mta | mail-transport-agent` become a dashed
+//! node named after them, with an `alternative` edge to each choice. In a
+//! status file, packages that are not installed are skipped, and a virtual
+//! package that is depended on gets a `Provides` edge to each installed
+//! package providing it.
+
+use anyhow::Context;
+
+use crate::Graph;
+
+const FIELDS: [&str; 4] = ["Pre-Depends", "Depends", "Recommends", "Suggests"];
+
+/// Parses a dpkg status file or a debian/control file.
+pub fn parse(input: &str) -> anyhow::Result<Graph> {
+    let mut graph = Graph::new();
+    let mut provides = Vec::new();
+    for paragraph in paragraphs(input)? {
+        let field = |name: &str| {
+            paragraph
+                .iter()
+                .find(|(field, _)| field.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+        let Some(package) = field("Package") else {
+            continue;
+        };
+        if field("Status").is_some_and(|status| !status.ends_with(" installed")) {
+            continue;
+        }
+        graph.insert_node(package);
+
+        for name in FIELDS {
+            let Some(value) = field(name) else {
+                continue;
+            };
+            for relation in value.split(',').map(str::trim) {
+                if relation.is_empty() || relation.starts_with("${") {
+                    continue;
+                }
+                let alternatives = relation
+                    .split('|')
+                    .map(parse_relation)
+                    .collect::<Option<Vec<_>>>()
+                    .with_context(|| {
+                        format!(
+                            "invalid relation \"{}\" in {} of {}",
+                            relation, name, package
+                        )
+                    })?;
+                let label = |constraint: Option<&str>, label: &str| match constraint {
+                    Some(constraint) => format!("{} ({})", label, constraint),
+                    None => label.to_string(),
+                };
+                if let [(to, constraint)] = alternatives.as_slice() {
+                    let label = label(constraint.as_deref(), name);
+                    super::add_edge(&mut graph, package, to, Some(label));
+                    continue;
+                }
+
+                let group = alternatives
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>();
+                let group = group.join(" | ");
+                let id = graph.insert_node(&group);
+                graph
+                    .node_attributes_mut(id)
+                    .insert("style".to_string(), "dashed".to_string());
+                super::add_edge(&mut graph, package, &group, Some(name.to_string()));
+                for (to, constraint) in alternatives.iter() {
+                    let label = label(constraint.as_deref(), "alternative");
+                    super::add_edge(&mut graph, &group, to, Some(label));
+                }
+            }
+        }
+        if let Some(value) = field("Provides") {
+            for relation in value.split(',').filter_map(parse_relation) {
+                provides.push((relation.0.to_string(), package.to_string()));
+            }
+        }
+    }
+
+    for (virtual_package, package) in provides {
+        let depended_on = graph
+            .node_id(&virtual_package)
+            .is_some_and(|id| graph.in_degree(id) > 0);
+        if depended_on {
+            let label = Some("Provides".to_string());
+            super::add_edge(&mut graph, &virtual_package, &package, label);
+        }
+    }
+    Ok(graph)
+}
+
+// the fields of every paragraph, with continuation lines joined
+fn paragraphs(input: &str) -> anyhow::Result<Vec<Vec<(&str, String)>>> {
+    let mut paragraphs = Vec::new();
+    let mut paragraph: Vec<(&str, String)> = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            if !paragraph.is_empty() {
+                paragraphs.push(std::mem::take(&mut paragraph));
+            }
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with([' ', '\t']) {
+            let (_, value) = paragraph.last_mut().with_context(|| {
+                format!(
+                    "error parsing line {}: continuation without a field",
+                    idx + 1
+                )
+            })?;
+            value.push(' ');
+            value.push_str(line.trim());
+            continue;
+        }
+        let (name, value) = line.split_once(':').with_context(|| {
+            format!(
+                "error parsing line {}: \"{}\" (expected a field)",
+                idx + 1,
+                line
+            )
+        })?;
+        paragraph.push((name.trim(), value.trim().to_string()));
+    }
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph);
+    }
+    Ok(paragraphs)
+}
+
+// `name:arch (op version) [arch] <profile>` as the name and `op version`
+fn parse_relation(relation: &str) -> Option<(&str, Option<String>)> {
+    let relation = relation.trim();
+    let end = relation
+        .find(|c: char| c.is_whitespace() || "([<".contains(c))
+        .unwrap_or(relation.len());
+    let name = relation[..end].split(':').next().unwrap_or_default();
+    if name.is_empty() {
+        return None;
+    }
+
+    let rest = relation[end..].trim_start();
+    let constraint = match rest.strip_prefix('(') {
+        Some(rest) => {
+            let (constraint, _) = rest.split_once(')')?;
+            let constraint = constraint.trim();
+            let op_end = constraint
+                .find(|c: char| !"<>=".contains(c))
+                .unwrap_or(constraint.len());
+            let (op, version) = constraint.split_at(op_end);
+            Some(format!("{} {}", op, version.trim()))
+        }
+        None => None,
+    };
+    Some((name, constraint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let input = "\
+Package: bsd-mailx
+Status: install ok installed
+Version: 8.1.2-0.20220412cvs-1
+Pre-Depends: libc6:amd64 (>=2.34)
+Depends: liblockfile1 (>= 1.01),
+ default-mta | mail-transport-agent
+Suggests: python3:any
+
+Package: exim4-daemon-light
+Status: install ok installed
+Provides: mail-transport-agent
+
+Package: postfix
+Status: deinstall ok config-files
+Provides: mail-transport-agent
+Depends: libc6
+";
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("bsd-mailx", "libc6", Some("Pre-Depends (>= 2.34)")),
+                ("bsd-mailx", "liblockfile1", Some("Depends (>= 1.01)")),
+                (
+                    "bsd-mailx",
+                    "default-mta | mail-transport-agent",
+                    Some("Depends")
+                ),
+                (
+                    "default-mta | mail-transport-agent",
+                    "default-mta",
+                    Some("alternative")
+                ),
+                (
+                    "default-mta | mail-transport-agent",
+                    "mail-transport-agent",
+                    Some("alternative")
+                ),
+                ("bsd-mailx", "python3", Some("Suggests")),
+                (
+                    "mail-transport-agent",
+                    "exim4-daemon-light",
+                    Some("Provides")
+                ),
+            ]
+        );
+        assert_eq!(graph.node_id("postfix"), None);
+        let group = graph.node_id("default-mta | mail-transport-agent").unwrap();
+        assert_eq!(graph.node_attributes(group)["style"], "dashed");
+    }
+
+    #[test]
+    fn test_parse_control() {
+        let input = "\
+# comment
+Source: deptree
+Build-Depends: debhelper-compat (= 13)
+
+Package: deptree
+Architecture: any
+Depends: ${shlibs:Depends}, ${misc:Depends}, graphviz [!hurd-i386] <!nocheck>,
+Recommends: xdot | gv (<< 4)
+";
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("deptree", "graphviz", Some("Depends")),
+                ("deptree", "xdot | gv", Some("Recommends")),
+                ("xdot | gv", "xdot", Some("alternative")),
+                ("xdot | gv", "gv", Some("alternative (<< 4)")),
+            ]
+        );
+
+        assert!(parse(" continuation\n").is_err());
+        assert!(parse("Package: a\nDepends: b | \n").is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! The plain edge-list format, one `from->to:label` edge per line.
+//!
+//! Nodes can carry attributes, inline as `a[shape=cylinder]->b` or on their
+//! own line as `node db [color=red, group=storage]`.
+
+use std::io::Write;
+
+use anyhow::Context;
+use itertools::Itertools;
+
+use crate::{Attributes, Edge, Graph, NodeId};
+
+/// Parses an edge list with the given delimiters.
+pub fn parse(input: &str, edge_delim: &str, label_delim: &str) -> anyhow::Result<Graph> {
+    let mut graph = Graph::new();
+    for (idx, input) in input.lines().enumerate() {
+        let line = parse_line(input, edge_delim, label_delim)
+            .with_context(|| format!("error parsing line {}: \"{}\"", idx + 1, input))?;
+        let (from, to, label) = match line {
+            Line::Node(node) => {
+                insert_node_spec(&mut graph, node);
+                continue;
+            }
+            Line::Edge(from, to, label) => (from, to, label),
+        };
+        let from = insert_node_spec(&mut graph, from);
+        let to = insert_node_spec(&mut graph, to);
+
+        let edge = Edge {
+            from,
+            to,
+            label: label.map(|s| s.to_string()),
+            attributes: Attributes::new(),
+        };
+        graph.add_edge(edge);
+    }
+    Ok(graph)
+}
+
+enum Line<'a> {
+    Node(NodeSpec<'a>),
+    Edge(NodeSpec<'a>, NodeSpec<'a>, Option<&'a str>),
+}
+
+struct NodeSpec<'a> {
+    name: &'a str,
+    attributes: Attributes,
+}
+
+fn parse_line<'a>(line: &'a str, edge_delim: &str, label_delim: &str) -> Option<Line<'a>> {
+    // parse line
+    // a->b:foo
+    // a[shape=cylinder]->b
+    // node db [color=red, group=storage]
+
+    let split = split_outside_brackets(line, label_delim);
+    let edge = split[0];
+    let label = split.get(1).copied();
+
+    let split = split_outside_brackets(edge, edge_delim);
+    if split.len() == 1 {
+        let node = line.strip_prefix("node ")?;
+        return Some(Line::Node(parse_node_spec(node.trim())?));
+    }
+    Some(Line::Edge(
+        parse_node_spec(split[0])?,
+        parse_node_spec(split[1])?,
+        label,
+    ))
+}
+
+// name or name[key=value, ...]
+fn parse_node_spec(spec: &str) -> Option<NodeSpec<'_>> {
+    let Some(rest) = spec.strip_suffix(']') else {
+        // an attribute list that is not closed, or followed by more text
+        if spec.contains('[') {
+            return None;
+        }
+        return Some(NodeSpec {
+            name: spec,
+            attributes: Attributes::new(),
+        });
+    };
+    let (name, attributes) = rest.split_once('[')?;
+    Some(NodeSpec {
+        name: name.trim_end(),
+        attributes: parse_attributes(attributes)?,
+    })
+}
+
+// key=value, key="quoted, value"; ...; a key without a value is an error
+fn parse_attributes(list: &str) -> Option<Attributes> {
+    let mut attributes = Attributes::new();
+    let mut chars = list.chars().peekable();
+    loop {
+        while chars
+            .next_if(|c| c.is_whitespace() || *c == ',' || *c == ';')
+            .is_some()
+        {}
+        if chars.peek().is_none() {
+            return Some(attributes);
+        }
+
+        let mut key = String::new();
+        loop {
+            match chars.next()? {
+                '=' => break,
+                c => key.push(c),
+            }
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => value.push(chars.next()?),
+                    c => value.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|&c| c != ',' && c != ';') {
+                value.push(c);
+            }
+        }
+
+        let key = key.trim();
+        if key.is_empty() {
+            return None;
+        }
+        attributes.insert(key.to_string(), value.trim_end().to_string());
+    }
+}
+
+// like str::split, but ignores delimiters inside [...] and quotes
+fn split_outside_brackets<'a>(s: &'a str, delim: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut start = 0;
+    let mut chars = s.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' if depth > 0 => quoted = !quoted,
+            '\\' if quoted => {
+                chars.next();
+            }
+            '[' if !quoted => depth += 1,
+            ']' if !quoted && depth > 0 => depth -= 1,
+            _ if depth == 0 && idx >= start && s[idx..].starts_with(delim) => {
+                parts.push(&s[start..idx]);
+                start = idx + delim.len();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Writes `graph` in the edge-list format: a `node` line for every node with
+/// attributes or without edges, then one line per edge.
+///
+/// Fails if a name or label contains one of the delimiters.
+pub fn write(
+    graph: &Graph,
+    edge_delim: &str,
+    label_delim: &str,
+    out: &mut impl Write,
+) -> anyhow::Result<()> {
+    let check = |s: &str| {
+        for delim in [edge_delim, label_delim] {
+            if s.contains(delim) {
+                return Err(anyhow::anyhow!(
+                    "\"{}\" contains the delimiter \"{}\", choose another one",
+                    s,
+                    delim
+                ));
+            }
+        }
+        Ok(())
+    };
+
+    for (id, name) in graph.nodes() {
+        check(name)?;
+        let attributes = graph.node_attributes(id);
+        if attributes.is_empty() && graph.out_degree(id) + graph.in_degree(id) > 0 {
+            continue;
+        }
+        let list = attributes
+            .iter()
+            .map(|(key, value)| {
+                let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+                format!("{}=\"{}\"", key, value)
+            })
+            .join(", ");
+        match list.is_empty() {
+            true => writeln!(out, "node {}", name)?,
+            false => writeln!(out, "node {} [{}]", name, list)?,
+        }
+    }
+    for edge in graph.edges() {
+        let from = graph.node_name(edge.from).unwrap();
+        let to = graph.node_name(edge.to).unwrap();
+        match &edge.label {
+            Some(label) => {
+                check(label)?;
+                writeln!(out, "{}{}{}{}{}", from, edge_delim, to, label_delim, label)?
+            }
+            None => writeln!(out, "{}{}{}", from, edge_delim, to)?,
+        }
+    }
+    Ok(())
+}
+
+fn insert_node_spec(graph: &mut Graph, node: NodeSpec) -> NodeId {
+    let id = graph.insert_node(node.name);
+    graph.node_attributes_mut(id).extend(node.attributes);
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = "\
+node db [color=red, group=storage]
+node lonely
+api[shape=cylinder]->db:query
+a[label=\"x, y]\"; tooltip=\"say \\\"hi\\\"\"]->b[color=blue]
+";
+        let graph = parse(input, "->", ":").unwrap();
+        let attributes = |name| graph.node_attributes(graph.node_id(name).unwrap());
+        assert_eq!(
+            graph.nodes().map(|(_, name)| name).collect::<Vec<_>>(),
+            vec!["db", "lonely", "api", "a", "b"]
+        );
+        assert_eq!(attributes("db")["color"], "red");
+        assert_eq!(attributes("db")["group"], "storage");
+        assert!(attributes("lonely").is_empty());
+        assert_eq!(attributes("api")["shape"], "cylinder");
+        assert_eq!(attributes("a")["label"], "x, y]");
+        assert_eq!(attributes("a")["tooltip"], "say \"hi\"");
+        assert_eq!(attributes("b")["color"], "blue");
+        let edge = graph.edge(0).unwrap();
+        assert_eq!(graph.node_name(edge.from), Some("api"));
+        assert_eq!(edge.label.as_deref(), Some("query"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in [
+            "a[shape=cylinder->b",
+            "a[shape]->b",
+            "a[label=\"x]->b",
+            "a[k=v]x->b",
+            "a->b[=red]",
+            "node db [color=red",
+        ] {
+            let err = parse(input, "->", ":").err();
+            assert!(err.is_some(), "parsed {}", input);
+            assert_eq!(
+                err.unwrap().to_string(),
+                format!("error parsing line 1: \"{}\"", input)
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let input = "node lonely [color=red]\na[shape=\"say \\\"hi\\\"\"]->b:uses\nb->c\n";
+        let graph = parse(input, "->", ":").unwrap();
+
+        let mut output = Vec::new();
+        write(&graph, "->", ":", &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            "node lonely [color=\"red\"]\nnode a [shape=\"say \\\"hi\\\"\"]\na->b:uses\nb->c\n"
+        );
+        let graph = parse(&output, "->", ":").unwrap();
+        let a = graph.node_id("a").unwrap();
+        assert_eq!(graph.node_attributes(a)["shape"], "say \"hi\"");
+
+        let mut graph = Graph::new();
+        graph.insert_node("crate::a");
+        assert!(write(&graph, "->", ":", &mut Vec::new()).is_err());
+    }
+}
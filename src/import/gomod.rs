@@ -0,0 +1,107 @@
+//! `go mod graph` importer.
+//!
+//! Every line holds a module and one of its requirements, separated by a
+//! space, e.g. `example.com/app golang.org/x/text@v0.3.7`. The main module is
+//! printed without a version.
+
+use std::collections::HashSet;
+
+use crate::Graph;
+
+/// Parses the output of `go mod graph`.
+///
+/// With `strip_versions`, all versions of a module are merged into one node
+/// named after the module path, and every edge is labelled with the version
+/// of the required module. Duplicate edges that result from the merge are
+/// dropped.
+pub fn parse(input: &str, strip_versions: bool) -> anyhow::Result<Graph> {
+    let mut graph = Graph::new();
+    let mut seen = HashSet::new();
+    for (idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let (Some(from), Some(to), None) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(anyhow::anyhow!(
+                "error parsing line {}: \"{}\" (expected two space-separated modules)",
+                idx + 1,
+                line
+            ));
+        };
+
+        let (from, to, label) = if strip_versions {
+            let (to, version) = split_version(to);
+            (split_version(from).0, to, version)
+        } else {
+            (from, to, None)
+        };
+        if seen.insert((from, to, label)) {
+            super::add_edge(&mut graph, from, to, label.map(|s| s.to_string()));
+        }
+    }
+    Ok(graph)
+}
+
+fn split_version(module: &str) -> (&str, Option<&str>) {
+    match module.split_once('@') {
+        Some((path, version)) => (path, Some(version)),
+        None => (module, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "\
+example.com/app golang.org/x/text@v0.3.7
+example.com/app rsc.io/quote@v1.5.2
+rsc.io/quote@v1.5.2 golang.org/x/text@v0.0.0-20170915032832-14c0d48ead0c
+rsc.io/quote@v1.5.2 rsc.io/sampler@v1.3.0
+rsc.io/sampler@v1.3.0 golang.org/x/text@v0.0.0-20170915032832-14c0d48ead0c
+";
+
+    #[test]
+    fn test_parse() {
+        let graph = parse(INPUT, false).unwrap();
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 5);
+        assert_eq!(graph.node_name(1), Some("golang.org/x/text@v0.3.7"));
+    }
+
+    #[test]
+    fn test_parse_strip_versions() {
+        let graph = parse(INPUT, true).unwrap();
+        let nodes = graph.nodes().map(|(_, name)| name).collect::<Vec<_>>();
+        assert_eq!(
+            nodes,
+            vec![
+                "example.com/app",
+                "golang.org/x/text",
+                "rsc.io/quote",
+                "rsc.io/sampler"
+            ]
+        );
+        let labels = graph
+            .edges()
+            .map(|edge| edge.label.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                "v0.3.7",
+                "v1.5.2",
+                "v0.0.0-20170915032832-14c0d48ead0c",
+                "v1.3.0",
+                "v0.0.0-20170915032832-14c0d48ead0c"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(parse("example.com/app\n", false).is_err());
+        assert!(parse("a b c\n", false).is_err());
+    }
+}
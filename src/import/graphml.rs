@@ -0,0 +1,339 @@
+//! GraphML, the interchange format of yEd, Gephi and NetworkX.
+//!
+//! Node ids become node names. `<data>` becomes node and edge attributes,
+//! named by the `attr.name` of its `<key>`, and `<key>` defaults fill in data
+//! an element leaves out. The `label` of an edge becomes its label.
+//!
+//! Undirected edges, from `edgedefault="undirected"` or `directed="false"`,
+//! keep their order and get `dir="none"` so they render without an arrow.
+//! Nested graphs are flattened, and data holding markup, like the graphics of
+//! yEd, is skipped.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+
+use anyhow::Context;
+use roxmltree::{Document, Node};
+
+use crate::{Attributes, Edge, Graph};
+
+struct Key<'a> {
+    name: &'a str,
+    domain: &'a str,
+    default: Option<&'a str>,
+}
+
+/// Parses a GraphML document.
+pub fn parse(input: &str) -> anyhow::Result<Graph> {
+    let doc = Document::parse(input).context("invalid GraphML")?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "graphml" {
+        return Err(anyhow::anyhow!(
+            "expected a graphml element, found {}",
+            root.tag_name().name()
+        ));
+    }
+    let line = |node: Node| doc.text_pos_at(node.range().start).row;
+
+    let mut keys = HashMap::new();
+    for key in root.children().filter(|n| n.has_tag_name("key")) {
+        let id = key
+            .attribute("id")
+            .with_context(|| format!("key without id at line {}", line(key)))?;
+        let default = key
+            .children()
+            .find(|n| n.has_tag_name("default"))
+            .map(|n| n.text().unwrap_or_default());
+        keys.insert(
+            id,
+            Key {
+                name: key.attribute("attr.name").unwrap_or(id),
+                domain: key.attribute("for").unwrap_or("all"),
+                default,
+            },
+        );
+    }
+    let data = |element: Node, domain: &str| {
+        let mut data = keys
+            .values()
+            .filter(|key| key.domain == domain || key.domain == "all")
+            .filter_map(|key| Some((key.name.to_string(), key.default?.to_string())))
+            .collect::<Attributes>();
+        for child in element.children().filter(|n| n.has_tag_name("data")) {
+            if child.children().any(|n| n.is_element()) {
+                continue;
+            }
+            let Some(key) = child.attribute("key") else {
+                continue;
+            };
+            let name = keys.get(key).map_or(key, |key| key.name);
+            let value = child.text().unwrap_or_default();
+            data.insert(name.to_string(), value.to_string());
+        }
+        data
+    };
+
+    let mut graph = Graph::new();
+    for node in root.descendants().filter(|n| n.has_tag_name("node")) {
+        let id = node
+            .attribute("id")
+            .with_context(|| format!("node without id at line {}", line(node)))?;
+        let id = graph.insert_node(id);
+        graph.node_attributes_mut(id).extend(data(node, "node"));
+    }
+    for edge in root.descendants().filter(|n| n.has_tag_name("edge")) {
+        let endpoint = |name| {
+            edge.attribute(name)
+                .with_context(|| format!("edge without {} at line {}", name, line(edge)))
+        };
+        let from = graph.insert_node(endpoint("source")?);
+        let to = graph.insert_node(endpoint("target")?);
+
+        let directed = match edge.attribute("directed") {
+            Some(directed) => directed == "true",
+            None => {
+                edge.ancestors()
+                    .find(|n| n.has_tag_name("graph"))
+                    .and_then(|graph| graph.attribute("edgedefault"))
+                    != Some("undirected")
+            }
+        };
+        let mut attributes = data(edge, "edge");
+        let label = attributes.remove("label");
+        if !directed {
+            attributes
+                .entry("dir".to_string())
+                .or_insert_with(|| "none".to_string());
+        }
+        graph.add_edge(Edge {
+            from,
+            to,
+            label,
+            attributes,
+        });
+    }
+    Ok(graph)
+}
+
+/// Writes `graph` as a GraphML graph called `name`.
+///
+/// Edges with `dir="none"` are written as undirected edges.
+pub fn write(graph: &Graph, name: &str, out: &mut impl Write) -> anyhow::Result<()> {
+    let node_keys = graph
+        .nodes()
+        .flat_map(|(id, _)| graph.node_attributes(id).keys().map(String::as_str))
+        .collect::<BTreeSet<_>>();
+    let edge_keys = graph
+        .edges()
+        .flat_map(|edge| edge_data(edge).map(|(key, _)| key))
+        .collect::<BTreeSet<_>>();
+    let mut key_ids = HashMap::new();
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        out,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    )?;
+    let keys = node_keys.iter().map(|key| ("node", key));
+    let keys = keys.chain(edge_keys.iter().map(|key| ("edge", key)));
+    for (idx, (domain, key)) in keys.enumerate() {
+        let id = format!("d{}", idx);
+        writeln!(
+            out,
+            "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"string\"/>",
+            id,
+            domain,
+            escape(key)
+        )?;
+        key_ids.insert((domain, *key), id);
+    }
+
+    let write_data = |out: &mut dyn Write, domain, key, value: &str| {
+        writeln!(
+            out,
+            "      <data key=\"{}\">{}</data>",
+            key_ids[&(domain, key)],
+            escape(value)
+        )
+    };
+    writeln!(
+        out,
+        "  <graph id=\"{}\" edgedefault=\"directed\">",
+        escape(name)
+    )?;
+    for (id, name) in graph.nodes() {
+        let attributes = graph.node_attributes(id);
+        if attributes.is_empty() {
+            writeln!(out, "    <node id=\"{}\"/>", escape(name))?;
+            continue;
+        }
+        writeln!(out, "    <node id=\"{}\">", escape(name))?;
+        for (key, value) in attributes.iter() {
+            write_data(out, "node", key, value)?;
+        }
+        writeln!(out, "    </node>")?;
+    }
+    for edge in graph.edges() {
+        let from = escape(graph.node_name(edge.from).unwrap());
+        let to = escape(graph.node_name(edge.to).unwrap());
+        let directed = match edge.attributes.get("dir").map(String::as_str) {
+            Some("none") => " directed=\"false\"",
+            _ => "",
+        };
+        let mut data = edge_data(edge).peekable();
+        if data.peek().is_none() {
+            writeln!(
+                out,
+                "    <edge source=\"{}\" target=\"{}\"{}/>",
+                from, to, directed
+            )?;
+            continue;
+        }
+        writeln!(
+            out,
+            "    <edge source=\"{}\" target=\"{}\"{}>",
+            from, to, directed
+        )?;
+        for (key, value) in data {
+            write_data(out, "edge", key, value)?;
+        }
+        writeln!(out, "    </edge>")?;
+    }
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")?;
+    Ok(())
+}
+
+// the label of an edge and its attributes, except the dir of undirected edges
+fn edge_data(edge: &Edge) -> impl Iterator<Item = (&str, &str)> {
+    let label = edge
+        .label
+        .as_deref()
+        .filter(|_| !edge.attributes.contains_key("label"));
+    let attributes = edge
+        .attributes
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .filter(|&(key, value)| !(key == "dir" && value == "none"));
+    label
+        .map(|label| ("label", label))
+        .into_iter()
+        .chain(attributes)
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns"
+         xmlns:y="http://www.yworks.com/xml/graphml">
+  <key id="d0" for="node" attr.name="color" attr.type="string">
+    <default>gray</default>
+  </key>
+  <key id="d1" for="edge" attr.name="label" attr.type="string"/>
+  <key id="d2" for="edge" attr.name="weight" attr.type="double"/>
+  <key id="d3" for="node" yfiles.type="nodegraphics"/>
+  <graph id="G" edgedefault="undirected">
+    <node id="app"><data key="d0">red</data></node>
+    <node id="lib">
+      <data key="d3"><y:ShapeNode><y:NodeLabel>lib</y:NodeLabel></y:ShapeNode></data>
+    </node>
+    <edge source="app" target="lib"><data key="d1">uses</data></edge>
+    <edge source="lib" target="log" directed="true">
+      <data key="d2">0.5</data>
+    </edge>
+  </graph>
+</graphml>"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![("app", "lib", Some("uses")), ("lib", "log", None)]
+        );
+        let color = |name| {
+            graph
+                .node_attributes(graph.node_id(name).unwrap())
+                .get("color")
+        };
+        assert_eq!(color("app").unwrap(), "red");
+        assert_eq!(color("lib").unwrap(), "gray");
+        assert_eq!(color("log"), None);
+        assert_eq!(graph.edge(0).unwrap().attributes["dir"], "none");
+        assert_eq!(graph.edge(1).unwrap().attributes["weight"], "0.5");
+        assert!(!graph.edge(1).unwrap().attributes.contains_key("dir"));
+
+        assert!(parse("<graph/>").is_err());
+        assert!(parse("<graphml><graph><edge source=\"a\"/></graph></graphml>").is_err());
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let input = r#"<graphml>
+  <key id="c" for="node" attr.name="color"/>
+  <graph edgedefault="directed">
+    <node id="a &amp; &lt;b&gt;"><data key="c">"red"</data></node>
+    <edge source="a &amp; &lt;b&gt;" target="c"><data key="label">x</data></edge>
+    <edge source="c" target="d" directed="false"/>
+  </graph>
+</graphml>"#;
+        let graph = parse(input).unwrap();
+
+        let mut output = Vec::new();
+        write(&graph, "G", &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="color" attr.type="string"/>
+  <key id="d1" for="edge" attr.name="label" attr.type="string"/>
+  <graph id="G" edgedefault="directed">
+    <node id="a &amp; &lt;b&gt;">
+      <data key="d0">&quot;red&quot;</data>
+    </node>
+    <node id="c"/>
+    <node id="d"/>
+    <edge source="a &amp; &lt;b&gt;" target="c">
+      <data key="d1">x</data>
+    </edge>
+    <edge source="c" target="d" directed="false"/>
+  </graph>
+</graphml>
+"#
+        );
+        let graph = parse(&output).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![("a & <b>", "c", Some("x")), ("c", "d", None)]
+        );
+        assert_eq!(graph.edge(1).unwrap().attributes["dir"], "none");
+    }
+}
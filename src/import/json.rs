@@ -0,0 +1,210 @@
+//! JSON importer.
+//!
+//! Reads either a graph object, `{"nodes": [...], "edges": [...]}`, or an
+//! adjacency map, `{"a": ["b", "c"]}`. NetworkX node-link data, with `links`
+//! of `source` and `target`, is read as a graph object.
+//!
+//! Nodes are names or objects with an `id` or `name`; edges are objects with
+//! `from` and `to`, and adjacency lists hold names or objects with a `to`.
+//! An edge may have a `label`. All other members of these objects become
+//! attributes, and so do the members of an `attributes` object.
+
+use anyhow::Context;
+use serde_json::{Map, Value};
+
+use crate::{Attributes, Edge, Graph};
+
+const NODE_KEYS: [&str; 2] = ["id", "name"];
+const EDGE_KEYS: [&str; 5] = ["from", "to", "source", "target", "label"];
+
+/// Parses a JSON graph object or adjacency map.
+pub fn parse(input: &str) -> anyhow::Result<Graph> {
+    let value: Value = serde_json::from_str(input).context("invalid JSON")?;
+    let object = value
+        .as_object()
+        .context("expected a JSON object of nodes and edges or an adjacency map")?;
+
+    let mut graph = Graph::new();
+    if is_graph_object(object) {
+        let nodes = object.get("nodes").and_then(Value::as_array);
+        for (idx, node) in nodes.into_iter().flatten().enumerate() {
+            let name = match node {
+                Value::Object(node) => NODE_KEYS
+                    .iter()
+                    .find_map(|key| node.get(*key))
+                    .map(scalar)
+                    .with_context(|| format!("node {} has no id or name", idx))?,
+                node => scalar(node),
+            };
+            let id = graph.insert_node(&name);
+            if let Value::Object(node) = node {
+                graph
+                    .node_attributes_mut(id)
+                    .extend(attributes(node, &NODE_KEYS));
+            }
+        }
+
+        let edges = ["edges", "links"]
+            .iter()
+            .filter_map(|key| object.get(*key)?.as_array())
+            .flatten();
+        for (idx, edge) in edges.enumerate() {
+            let edge = edge
+                .as_object()
+                .with_context(|| format!("edge {} is not an object", idx))?;
+            let endpoint = |keys: [&str; 2]| {
+                keys.iter()
+                    .find_map(|key| edge.get(*key))
+                    .map(scalar)
+                    .with_context(|| format!("edge {} has no {}", idx, keys[0]))
+            };
+            let from = endpoint(["from", "source"])?;
+            let to = endpoint(["to", "target"])?;
+            add_edge(&mut graph, &from, &to, edge);
+        }
+        return Ok(graph);
+    }
+
+    for (from, targets) in object.iter() {
+        graph.insert_node(from);
+        let targets = targets
+            .as_array()
+            .with_context(|| format!("the dependencies of \"{}\" are not an array", from))?;
+        for target in targets {
+            match target {
+                Value::Object(edge) => {
+                    let to = edge
+                        .get("to")
+                        .map(scalar)
+                        .with_context(|| format!("a dependency of \"{}\" has no to", from))?;
+                    add_edge(&mut graph, from, &to, edge);
+                }
+                to => add_edge(&mut graph, from, &scalar(to), &Map::new()),
+            }
+        }
+    }
+    Ok(graph)
+}
+
+// an object with nodes, edges or links, and no other arrays that could be
+// adjacency lists
+fn is_graph_object(object: &Map<String, Value>) -> bool {
+    let keys = ["nodes", "edges", "links"];
+    keys.iter()
+        .any(|key| object.get(*key).is_some_and(Value::is_array))
+        && object
+            .iter()
+            .all(|(key, value)| !value.is_array() || keys.contains(&key.as_str()))
+}
+
+fn add_edge(graph: &mut Graph, from: &str, to: &str, edge: &Map<String, Value>) {
+    let from = graph.insert_node(from);
+    let to = graph.insert_node(to);
+    graph.add_edge(Edge {
+        from,
+        to,
+        label: edge.get("label").map(scalar),
+        attributes: attributes(edge, &EDGE_KEYS),
+    });
+}
+
+fn attributes(object: &Map<String, Value>, reserved: &[&str]) -> Attributes {
+    let mut attributes = Attributes::new();
+    for (key, value) in object.iter() {
+        match (key.as_str(), value) {
+            ("attributes", Value::Object(nested)) => {
+                attributes.extend(nested.iter().map(|(k, v)| (k.clone(), scalar(v))));
+            }
+            (key, _) if reserved.contains(&key) => {}
+            (key, value) => {
+                attributes.insert(key.to_string(), scalar(value));
+            }
+        }
+    }
+    attributes
+}
+
+// strings without their quotes, anything else as JSON
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_graph_object() {
+        let input = r#"{
+            "nodes": [
+                "lonely",
+                {"id": "db", "color": "red", "attributes": {"shape": "cylinder"}},
+                {"name": "a->b: c"}
+            ],
+            "edges": [
+                {"from": "a->b: c", "to": "db", "label": "reads", "weight": 2},
+                {"from": "api", "to": "db"}
+            ]
+        }"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![("a->b: c", "db", Some("reads")), ("api", "db", None)]
+        );
+        assert_eq!(graph.node_count(), 4);
+        let db = graph.node_attributes(graph.node_id("db").unwrap());
+        assert_eq!(db["color"], "red");
+        assert_eq!(db["shape"], "cylinder");
+        assert_eq!(graph.edge(0).unwrap().attributes["weight"], "2");
+        assert!(graph.edge(1).unwrap().attributes.is_empty());
+    }
+
+    #[test]
+    fn test_parse_node_link() {
+        let input = r#"{
+            "directed": true,
+            "graph": {},
+            "nodes": [{"id": 1}, {"id": 2}],
+            "links": [{"source": 1, "target": 2}]
+        }"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(edges(&graph), vec![("1", "2", None)]);
+    }
+
+    #[test]
+    fn test_parse_adjacency() {
+        let input = r#"{
+            "a": ["b", {"to": "c", "label": "dev", "style": "dashed"}],
+            "nodes": ["a"],
+            "c": []
+        }"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("a", "b", None),
+                ("a", "c", Some("dev")),
+                ("nodes", "a", None),
+            ]
+        );
+        assert_eq!(graph.edge(1).unwrap().attributes["style"], "dashed");
+        assert!(parse(r#"{"a": "b"}"#).is_err());
+        assert!(parse("[]").is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! `mvn dependency:tree` and `gradle dependencies` importers.
+//!
+//! Artifacts become `group:artifact:version` nodes and edges are labelled
+//! with the Maven scope or Gradle configuration. When conflict resolution
+//! picked another version than the requested one, the edge points to the
+//! picked version, is drawn red and its label shows `requested -> picked`.
+
+use std::collections::HashSet;
+
+use crate::{Attributes, Edge, Graph, NodeId};
+
+/// Parses the output of `mvn dependency:tree`, with or without `-Dverbose`.
+///
+/// The `[INFO]` prefixes of a build log may be left in; every module of a
+/// multi-module build is read.
+pub fn parse_maven_tree(input: &str) -> anyhow::Result<Graph> {
+    let mut tree = Tree::new();
+    for (idx, line) in input.lines().enumerate() {
+        let line = strip_log_level(line);
+        let Some((depth, entry)) = split_tree_prefix(line, 3) else {
+            // a module line like "com.example:app:jar:1.0"
+            let parts = line.split(':').collect::<Vec<_>>();
+            if (4..=5).contains(&parts.len()) && !line.contains(char::is_whitespace) {
+                let name = format!("{}:{}:{}", parts[0], parts[1], parts[parts.len() - 1]);
+                tree.root(&name);
+            } else {
+                tree.end();
+            }
+            continue;
+        };
+        if !tree.in_tree() {
+            continue;
+        }
+
+        // verbose output: "(g:a:jar:1.0:compile - omitted for conflict with 2.0)"
+        let (entry, note) = match entry.strip_prefix('(').and_then(|e| e.strip_suffix(')')) {
+            Some(entry) => match entry.split_once(" - ") {
+                Some((entry, note)) => (entry, Some(note)),
+                None => (entry, None),
+            },
+            None => (entry, None),
+        };
+        // trailing notes: "(optional)", "(version managed from 1.0)", ...
+        let mut entry = entry;
+        let mut optional = false;
+        while let Some((rest, note)) = entry
+            .strip_suffix(')')
+            .and_then(|entry| entry.rsplit_once(" ("))
+        {
+            optional |= note == "optional";
+            entry = rest;
+        }
+        let parts = entry.split(':').collect::<Vec<_>>();
+        if !(5..=6).contains(&parts.len()) {
+            return Err(anyhow::anyhow!(
+                "error parsing line {}: \"{}\" (expected group:artifact:type:version:scope)",
+                idx + 1,
+                line
+            ));
+        }
+        let version = parts[parts.len() - 2];
+        let mut scope = parts[parts.len() - 1].to_string();
+        if optional {
+            scope.push_str(", optional");
+        }
+        let picked = note
+            .and_then(|note| note.split("omitted for conflict with ").nth(1))
+            .map(|v| v.split(';').next().unwrap_or(v).trim());
+
+        let name = format!("{}:{}:{}", parts[0], parts[1], picked.unwrap_or(version));
+        tree.add(depth, &name, scope, picked.map(|picked| (version, picked)));
+    }
+    Ok(tree.graph)
+}
+
+/// Parses the output of `gradle dependencies`.
+///
+/// Projects are named `project :path`, the root project after its name.
+/// Dependency constraints `(c)` are kept like dependencies.
+pub fn parse_gradle_dependencies(input: &str) -> anyhow::Result<Graph> {
+    let mut tree = Tree::new();
+    let mut project = "project".to_string();
+    let mut configuration = None;
+    for line in input.lines() {
+        let Some((depth, entry)) = split_tree_prefix(line, 5) else {
+            let line = line.trim();
+            // "Root project 'demo'" or "Project ':lib' - description"
+            let header = line
+                .strip_prefix("Root project ")
+                .or_else(|| line.strip_prefix("Project "));
+            if let Some(header) = header {
+                let name = header.split('\'').nth(1).unwrap_or(header);
+                project = format!("project {}", name);
+            } else if !line.is_empty() {
+                // "compileClasspath - Compile classpath for source set 'main'."
+                configuration = line.split_whitespace().next().map(str::to_string);
+            }
+            tree.end();
+            continue;
+        };
+        let Some(configuration) = configuration.as_ref() else {
+            continue;
+        };
+        if !tree.in_tree() {
+            tree.root(&project);
+        }
+
+        let mut entry = entry.trim();
+        for marker in [" (*)", " (c)", " (n)", " FAILED"] {
+            entry = entry.strip_suffix(marker).unwrap_or(entry).trim_end();
+        }
+        let (requested, picked) = match entry.split_once(" -> ") {
+            Some((requested, picked)) => (requested, Some(picked)),
+            None => (entry, None),
+        };
+        let parts = requested.splitn(3, ':').collect::<Vec<_>>();
+        let (name, conflict) = match (parts.as_slice(), picked) {
+            ([group, artifact, version], Some(picked)) => (
+                format!("{}:{}:{}", group, artifact, picked),
+                (*version != picked).then_some((*version, picked)),
+            ),
+            // the version came from a platform or constraint
+            ([group, artifact], Some(picked)) => {
+                (format!("{}:{}:{}", group, artifact, picked), None)
+            }
+            _ => (requested.to_string(), None),
+        };
+        tree.add(depth, &name, configuration.clone(), conflict);
+    }
+    Ok(tree.graph)
+}
+
+// builds the graph of an indented tree, one entry at a time
+struct Tree {
+    graph: Graph,
+    // the current entry at each depth, the root at depth 0
+    stack: Vec<NodeId>,
+    seen: HashSet<(NodeId, NodeId, String)>,
+}
+
+impl Tree {
+    fn new() -> Self {
+        Tree {
+            graph: Graph::new(),
+            stack: Vec::new(),
+            seen: HashSet::new(),
+        }
+    }
+
+    fn root(&mut self, name: &str) {
+        self.stack = vec![self.graph.insert_node(name)];
+    }
+
+    fn end(&mut self) {
+        self.stack.clear();
+    }
+
+    fn in_tree(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    // adds the entry `name` at `depth` (1 for children of the root)
+    fn add(&mut self, depth: usize, name: &str, label: String, conflict: Option<(&str, &str)>) {
+        let to = self.graph.insert_node(name);
+        self.stack.truncate(depth.min(self.stack.len()));
+        let Some(&from) = self.stack.last() else {
+            return;
+        };
+        self.stack.push(to);
+
+        let mut attributes = Attributes::new();
+        let label = match conflict {
+            Some((requested, picked)) => {
+                attributes.insert("color".to_string(), "red".to_string());
+                attributes.insert("fontcolor".to_string(), "red".to_string());
+                format!("{} ({} -> {})", label, requested, picked)
+            }
+            None => label,
+        };
+        if self.seen.insert((from, to, label.clone())) {
+            self.graph.add_edge(Edge {
+                from,
+                to,
+                label: Some(label),
+                attributes,
+            });
+        }
+    }
+}
+
+fn strip_log_level(line: &str) -> &str {
+    for level in ["[INFO] ", "[WARNING] ", "[DEBUG] "] {
+        if let Some(line) = line.strip_prefix(level) {
+            return line;
+        }
+    }
+    line
+}
+
+// splits "|  +- entry" into its depth and entry, for prefixes of `width`
+// characters per level like "+- " (maven) or "+--- " (gradle)
+fn split_tree_prefix(line: &str, width: usize) -> Option<(usize, &str)> {
+    let start = line.find(|c: char| !"+-\\| ".contains(c))?;
+    let prefix = &line[..start];
+    if !(prefix.ends_with("- ") && (prefix.contains('+') || prefix.contains('\\'))) {
+        return None;
+    }
+    Some((start / width, &line[start..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_maven_tree_managed() {
+        let input = r"[INFO] com.example:app:jar:1.0
+[INFO] +- org.slf4j:slf4j-api:jar:2.0.9:compile (version managed from 1.7.36)
+[INFO] +- com.h2database:h2:jar:2.2.224:compile (scope managed from runtime) (optional)
+[INFO] \- (commons-io:commons-io:jar:2.15.0:compile - version managed from 2.11.0; omitted for duplicate)
+";
+        let graph = parse_maven_tree(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (
+                    "com.example:app:1.0",
+                    "org.slf4j:slf4j-api:2.0.9",
+                    Some("compile")
+                ),
+                (
+                    "com.example:app:1.0",
+                    "com.h2database:h2:2.2.224",
+                    Some("compile, optional")
+                ),
+                (
+                    "com.example:app:1.0",
+                    "commons-io:commons-io:2.15.0",
+                    Some("compile")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_maven_tree() {
+        let input = r"[INFO] Scanning for projects...
+[INFO] --- maven-dependency-plugin:3.6.1:tree (default-cli) @ app ---
+[INFO] com.example:app:jar:1.0-SNAPSHOT
+[INFO] +- org.springframework:spring-core:jar:5.3.0:compile
+[INFO] |  +- org.springframework:spring-jcl:jar:5.3.0:compile
+[INFO] |  \- (com.google.guava:guava:jar:29.0-jre:compile - omitted for conflict with 31.0-jre)
+[INFO] +- com.google.guava:guava:jar:31.0-jre:compile
+[INFO] +- com.h2database:h2:jar:2.2.224:runtime (optional)
+[INFO] \- org.junit.jupiter:junit-jupiter:jar:tests:5.10.0:test
+[INFO]    \- (org.springframework:spring-jcl:jar:5.3.0:test - omitted for duplicate)
+[INFO] ------------------------------------------------------------------------
+[INFO] BUILD SUCCESS
+";
+        let graph = parse_maven_tree(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (
+                    "com.example:app:1.0-SNAPSHOT",
+                    "org.springframework:spring-core:5.3.0",
+                    Some("compile")
+                ),
+                (
+                    "org.springframework:spring-core:5.3.0",
+                    "org.springframework:spring-jcl:5.3.0",
+                    Some("compile")
+                ),
+                (
+                    "org.springframework:spring-core:5.3.0",
+                    "com.google.guava:guava:31.0-jre",
+                    Some("compile (29.0-jre -> 31.0-jre)")
+                ),
+                (
+                    "com.example:app:1.0-SNAPSHOT",
+                    "com.google.guava:guava:31.0-jre",
+                    Some("compile")
+                ),
+                (
+                    "com.example:app:1.0-SNAPSHOT",
+                    "com.h2database:h2:2.2.224",
+                    Some("runtime, optional")
+                ),
+                (
+                    "com.example:app:1.0-SNAPSHOT",
+                    "org.junit.jupiter:junit-jupiter:5.10.0",
+                    Some("test")
+                ),
+                (
+                    "org.junit.jupiter:junit-jupiter:5.10.0",
+                    "org.springframework:spring-jcl:5.3.0",
+                    Some("test")
+                ),
+            ]
+        );
+        assert_eq!(graph.edge(2).unwrap().attributes["color"], "red");
+        assert!(graph.edge(3).unwrap().attributes.is_empty());
+    }
+
+    #[test]
+    fn test_parse_gradle_dependencies() {
+        let input = r"
+------------------------------------------------------------
+Root project 'demo' - The demo application
+------------------------------------------------------------
+
+compileClasspath - Compile classpath for source set 'main'.
++--- project :lib
+|    \--- com.google.guava:guava:30.0-jre -> 31.0-jre
+|         \--- com.google.guava:failureaccess:1.0.1
++--- com.google.guava:guava:31.0-jre (*)
+\--- org.slf4j:slf4j-api -> 2.0.9
+
+testCompileClasspath - Compile classpath for source set 'test'.
+No dependencies
+
+(*) - Indicates repeated occurrences of a transitive dependency subtree.
+";
+        let graph = parse_gradle_dependencies(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("project demo", "project :lib", Some("compileClasspath")),
+                (
+                    "project :lib",
+                    "com.google.guava:guava:31.0-jre",
+                    Some("compileClasspath (30.0-jre -> 31.0-jre)")
+                ),
+                (
+                    "com.google.guava:guava:31.0-jre",
+                    "com.google.guava:failureaccess:1.0.1",
+                    Some("compileClasspath")
+                ),
+                (
+                    "project demo",
+                    "com.google.guava:guava:31.0-jre",
+                    Some("compileClasspath")
+                ),
+                (
+                    "project demo",
+                    "org.slf4j:slf4j-api:2.0.9",
+                    Some("compileClasspath")
+                ),
+            ]
+        );
+        assert_eq!(graph.edge(1).unwrap().attributes["color"], "red");
+    }
+}
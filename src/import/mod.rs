@@ -0,0 +1,394 @@
+//! Importers that turn the dependency formats of other tools into a [`Graph`].
+//!
+//! Every format is an [`Importer`]; a [`Registry`] finds them by name or
+//! guesses the format of an input. Library users can register their own.
+
+use std::io::Read;
+
+use anyhow::Context;
+
+use crate::{Attributes, Edge, Graph};
+
+pub mod cargo;
+pub mod depfile;
+pub mod dot;
+pub mod dpkg;
+pub mod edges;
+pub mod gomod;
+pub mod graphml;
+pub mod json;
+pub mod jvm;
+pub mod npm;
+pub mod pnpm;
+pub mod python;
+pub mod yarn;
+
+/// Settings of the importers; each importer uses the ones that apply to it.
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// Separates the nodes of an edge in the edge-list format
+    pub edge_delimiter: String,
+    /// Separates an edge from its label in the edge-list format
+    pub label_delimiter: String,
+    /// Merge all versions of a module into one node (go-mod-graph)
+    pub strip_versions: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        ImportOptions {
+            edge_delimiter: "->".to_string(),
+            label_delimiter: ":".to_string(),
+            strip_versions: false,
+        }
+    }
+}
+
+/// An input format.
+pub trait Importer {
+    /// Name of the format, e.g. `cargo-lock`.
+    fn name(&self) -> &'static str;
+
+    /// One line describing the format.
+    fn description(&self) -> &'static str;
+
+    /// Whether `input` looks like this format.
+    fn detect(&self, _input: &str) -> bool {
+        false
+    }
+
+    fn import(&self, reader: &mut dyn Read, options: &ImportOptions) -> anyhow::Result<Graph>;
+}
+
+/// An [`Importer`] made of plain functions.
+pub struct FnImporter {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub detect: fn(&str) -> bool,
+    pub parse: fn(&str, &ImportOptions) -> anyhow::Result<Graph>,
+}
+
+impl Importer for FnImporter {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn detect(&self, input: &str) -> bool {
+        (self.detect)(input)
+    }
+
+    fn import(&self, reader: &mut dyn Read, options: &ImportOptions) -> anyhow::Result<Graph> {
+        let mut input = String::new();
+        reader
+            .read_to_string(&mut input)
+            .context("failed to read input")?;
+        (self.parse)(&input, options)
+    }
+}
+
+/// Importers by name.
+///
+/// [`Registry::default`] has all built-in formats, with `edges` first.
+pub struct Registry {
+    importers: Vec<Box<dyn Importer>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Registry::new();
+        for importer in builtin() {
+            registry.register(importer);
+        }
+        registry
+    }
+}
+
+impl Registry {
+    /// Returns a registry without any importer.
+    pub fn new() -> Self {
+        Registry {
+            importers: Vec::new(),
+        }
+    }
+
+    /// Adds `importer`, replacing the one with the same name.
+    pub fn register(&mut self, importer: impl Importer + 'static) {
+        let importer = Box::new(importer);
+        match self
+            .importers
+            .iter()
+            .position(|i| i.name() == importer.name())
+        {
+            Some(idx) => self.importers[idx] = importer,
+            None => self.importers.push(importer),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Importer> {
+        self.importers().find(|i| i.name() == name)
+    }
+
+    /// Returns the importers in registration order.
+    pub fn importers(&self) -> impl Iterator<Item = &dyn Importer> + '_ {
+        self.importers.iter().map(|i| i.as_ref())
+    }
+
+    /// Returns the first importer that recognizes `input`.
+    pub fn detect(&self, input: &str) -> Option<&dyn Importer> {
+        self.importers().find(|i| i.detect(input))
+    }
+
+    /// Imports `input` with the importer called `name`.
+    pub fn import(
+        &self,
+        name: &str,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> anyhow::Result<Graph> {
+        let importer = self.get(name).with_context(|| {
+            let names = self.importers().map(|i| i.name()).collect::<Vec<_>>();
+            format!(
+                "unknown input format \"{}\" (expected one of {})",
+                name,
+                names.join(", ")
+            )
+        })?;
+        importer.import(reader, options)
+    }
+}
+
+// in the order formats are detected: specific formats first, and the
+// catch-all depfile last
+fn builtin() -> Vec<FnImporter> {
+    vec![
+        FnImporter {
+            name: "edges",
+            description: "One `from->to:label` edge per line",
+            detect: |_| false,
+            parse: |input, options| {
+                edges::parse(input, &options.edge_delimiter, &options.label_delimiter)
+            },
+        },
+        FnImporter {
+            name: "cargo-lock",
+            description: "Cargo.lock",
+            detect: |input| input.contains("@generated by Cargo"),
+            parse: |input, _| cargo::parse_lock(input),
+        },
+        FnImporter {
+            name: "cargo-metadata",
+            description: "JSON printed by `cargo metadata --format-version 1`",
+            detect: |input| is_json_object(input) && input.contains("\"workspace_members\""),
+            parse: |input, _| cargo::parse_metadata(input),
+        },
+        FnImporter {
+            name: "go-mod-graph",
+            description: "Output of `go mod graph`",
+            detect: |input| {
+                let mut lines = input.lines().filter(|l| !l.trim().is_empty()).peekable();
+                lines.peek().is_some()
+                    && lines.take(10).all(|line| {
+                        let fields = line.split_whitespace().collect::<Vec<_>>();
+                        fields.len() == 2 && fields[1].contains("@v")
+                    })
+            },
+            parse: |input, options| gomod::parse(input, options.strip_versions),
+        },
+        FnImporter {
+            name: "package-lock",
+            description: "package-lock.json (lockfile version 2 or 3)",
+            detect: |input| is_json_object(input) && input.contains("\"lockfileVersion\""),
+            parse: |input, _| npm::parse_package_lock(input),
+        },
+        FnImporter {
+            name: "yarn-lock",
+            description: "yarn.lock (classic or berry)",
+            detect: |input| input.contains("# yarn lockfile v1") || input.contains("\n__metadata:"),
+            parse: |input, _| yarn::parse(input),
+        },
+        FnImporter {
+            name: "pnpm-lock",
+            description: "pnpm-lock.yaml",
+            detect: |input| input.starts_with("lockfileVersion:"),
+            parse: |input, _| pnpm::parse(input),
+        },
+        FnImporter {
+            name: "pipdeptree",
+            description: "JSON printed by `pipdeptree --json-tree`",
+            detect: |input| {
+                input.trim_start().starts_with('[') && input.contains("\"installed_version\"")
+            },
+            parse: |input, _| python::parse_pipdeptree(input),
+        },
+        FnImporter {
+            name: "json",
+            description: "JSON graph object of nodes and edges, or adjacency map",
+            detect: is_json_object,
+            parse: |input, _| json::parse(input),
+        },
+        FnImporter {
+            name: "graphml",
+            description: "GraphML (yEd, Gephi, NetworkX)",
+            detect: |input| input.contains("<graphml"),
+            parse: |input, _| graphml::parse(input),
+        },
+        FnImporter {
+            name: "uv-lock",
+            description: "uv.lock",
+            detect: |input| input.starts_with("version = ") && input.contains("\n[[package]]"),
+            parse: |input, _| python::parse_uv_lock(input),
+        },
+        FnImporter {
+            name: "poetry-lock",
+            description: "poetry.lock",
+            detect: |input| input.contains("@generated by Poetry"),
+            parse: |input, _| python::parse_poetry_lock(input),
+        },
+        FnImporter {
+            name: "dot",
+            description: "Graphviz DOT",
+            detect: |input| {
+                let first = input.trim_start();
+                let first = first
+                    .split(|c: char| !c.is_ascii_alphabetic())
+                    .next()
+                    .unwrap_or_default();
+                ["digraph", "graph", "strict"]
+                    .iter()
+                    .any(|keyword| first.eq_ignore_ascii_case(keyword))
+            },
+            parse: |input, _| dot::parse(input),
+        },
+        FnImporter {
+            name: "maven-tree",
+            description: "Output of `mvn dependency:tree`",
+            detect: |input| {
+                input.lines().any(|line| {
+                    let line = line.strip_prefix("[INFO] ").unwrap_or(line);
+                    line.starts_with("+- ") || line.starts_with("\\- ")
+                })
+            },
+            parse: |input, _| jvm::parse_maven_tree(input),
+        },
+        FnImporter {
+            name: "gradle-dependencies",
+            description: "Output of `gradle dependencies`",
+            detect: |input| {
+                input
+                    .lines()
+                    .any(|line| line.starts_with("+--- ") || line.starts_with("\\--- "))
+            },
+            parse: |input, _| jvm::parse_gradle_dependencies(input),
+        },
+        FnImporter {
+            name: "dpkg-status",
+            description: "/var/lib/dpkg/status or debian/control",
+            detect: |input| {
+                let first = input
+                    .lines()
+                    .find(|l| !l.trim().is_empty() && !l.starts_with('#'));
+                first.is_some_and(|line| {
+                    line.starts_with("Package: ") || line.starts_with("Source: ")
+                })
+            },
+            parse: |input, _| dpkg::parse(input),
+        },
+        FnImporter {
+            name: "depfile",
+            description: "Make-style depfile (`gcc -MD`, `rustc --emit=dep-info`, `ninja -t deps`)",
+            detect: |input| {
+                let mut lines = input
+                    .lines()
+                    .filter(|l| !l.trim().is_empty() && !l.starts_with('#'));
+                lines.next().is_some_and(|line| {
+                    line.contains(": ") || line.ends_with(':') || line.ends_with('\\')
+                }) && !input.contains("->")
+            },
+            parse: |input, _| depfile::parse(input),
+        },
+    ]
+}
+
+fn is_json_object(input: &str) -> bool {
+    input.trim_start().starts_with('{')
+}
+
+pub(crate) fn add_edge(graph: &mut Graph, from: &str, to: &str, label: Option<String>) {
+    let from = graph.insert_node(from);
+    let to = graph.insert_node(to);
+    graph.add_edge(Edge {
+        from,
+        to,
+        label,
+        attributes: Attributes::new(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let registry = Registry::default();
+        let detected = |input: &str| registry.detect(input).map(|i| i.name());
+        assert_eq!(detected("a->b\nb->c:label\n"), None);
+        assert_eq!(detected("digraph{ a -> b }"), Some("dot"));
+        assert_eq!(
+            detected("example.com/app golang.org/x/text@v0.3.0\n"),
+            Some("go-mod-graph")
+        );
+        assert_eq!(detected("main.o: main.c \\\n  util.h\n"), Some("depfile"));
+        assert_eq!(detected("Package: adduser\n"), Some("dpkg-status"));
+        assert_eq!(detected("lockfileVersion: '9.0'\n"), Some("pnpm-lock"));
+        assert_eq!(detected("# yarn lockfile v1\n"), Some("yarn-lock"));
+        assert_eq!(detected("{\"a\": [\"b\"]}"), Some("json"));
+        assert_eq!(
+            detected("<?xml version=\"1.0\"?>\n<graphml>"),
+            Some("graphml")
+        );
+        assert_eq!(
+            detected("[INFO] \\- junit:junit:jar:4.13:test\n"),
+            Some("maven-tree")
+        );
+
+        let options = ImportOptions {
+            edge_delimiter: " => ".to_string(),
+            ..Default::default()
+        };
+        let graph = registry
+            .import("edges", &mut "a => b:x\n".as_bytes(), &options)
+            .unwrap();
+        assert_eq!(graph.edge(0).unwrap().label.as_deref(), Some("x"));
+        assert!(registry
+            .import("nope", &mut "".as_bytes(), &options)
+            .is_err());
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = Registry::new();
+        registry.register(FnImporter {
+            name: "lines",
+            description: "One node per line",
+            detect: |_| true,
+            parse: |input, _| {
+                let mut graph = Graph::new();
+                input.lines().for_each(|line| {
+                    graph.insert_node(line);
+                });
+                Ok(graph)
+            },
+        });
+        let importer = registry.detect("a\nb\n").unwrap();
+        assert_eq!(importer.name(), "lines");
+        let graph = importer
+            .import(&mut "a\nb\n".as_bytes(), &ImportOptions::default())
+            .unwrap();
+        assert_eq!(graph.node_count(), 2);
+    }
+}
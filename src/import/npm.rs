@@ -0,0 +1,204 @@
+//! `package-lock.json` (lockfile version 2 and 3) importer.
+//!
+//! Packages become `name@version` nodes. Edges are labelled with the kind of
+//! the dependency: `prod`, `dev`, `optional` or `peer`.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::Graph;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageLock {
+    name: Option<String>,
+    version: Option<String>,
+    lockfile_version: u32,
+    #[serde(default)]
+    packages: BTreeMap<String, Package>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Package {
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    link: bool,
+    resolved: Option<String>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: BTreeMap<String, String>,
+}
+
+impl Package {
+    // (name, kind) of every dependency; optional dependencies are also
+    // listed in `dependencies`, but only reported once
+    fn dependencies(&self) -> Vec<(&str, &'static str)> {
+        let mut dependencies = Vec::new();
+        for name in self.dependencies.keys() {
+            if !self.optional_dependencies.contains_key(name) {
+                dependencies.push((name.as_str(), "prod"));
+            }
+        }
+        for (deps, kind) in [
+            (&self.dev_dependencies, "dev"),
+            (&self.optional_dependencies, "optional"),
+            (&self.peer_dependencies, "peer"),
+        ] {
+            dependencies.extend(deps.keys().map(|name| (name.as_str(), kind)));
+        }
+        dependencies
+    }
+}
+
+/// Parses a `package-lock.json` or `npm-shrinkwrap.json` file.
+///
+/// Dependencies are resolved the way node does, by looking for
+/// `node_modules/<name>` next to the package and then in every parent
+/// directory. Dependencies that are not installed, e.g. optional ones for
+/// another platform, are skipped.
+pub fn parse_package_lock(input: &str) -> anyhow::Result<Graph> {
+    let lock: PackageLock = serde_json::from_str(input).context("invalid package-lock.json")?;
+    if lock.lockfile_version < 2 {
+        return Err(anyhow::anyhow!(
+            "lockfileVersion {} is not supported, only 2 and 3",
+            lock.lockfile_version
+        ));
+    }
+
+    let node = |path: &str| -> Option<String> {
+        let mut path = path;
+        let mut package = lock.packages.get(path)?;
+        if package.link {
+            path = package.resolved.as_deref()?;
+            package = lock.packages.get(path)?;
+        }
+
+        let name = match (&package.name, path) {
+            (Some(name), _) => name.as_str(),
+            (None, "") => lock.name.as_deref().unwrap_or("root"),
+            (None, path) => match path.rfind("node_modules/") {
+                Some(idx) => &path[idx + "node_modules/".len()..],
+                None => path,
+            },
+        };
+        let version = match path {
+            "" => package.version.as_ref().or(lock.version.as_ref()),
+            _ => package.version.as_ref(),
+        };
+        Some(match version {
+            Some(version) => format!("{}@{}", name, version),
+            None => name.to_string(),
+        })
+    };
+
+    let mut graph = Graph::new();
+    for (path, package) in lock.packages.iter() {
+        if package.link {
+            continue;
+        }
+        let from = node(path).unwrap();
+        graph.insert_node(&from);
+
+        for (name, kind) in package.dependencies() {
+            let Some(to) = resolve(path, name, |path| lock.packages.contains_key(path)) else {
+                log::debug!("{} {} dependency {} is not installed", from, kind, name);
+                continue;
+            };
+            match node(&to) {
+                Some(to) => super::add_edge(&mut graph, &from, &to, Some(kind.to_string())),
+                None => log::debug!("{} links to a missing package", to),
+            };
+        }
+    }
+    Ok(graph)
+}
+
+// node's module resolution: `<dir>/node_modules/<name>` for `dir` and each of
+// its parents
+fn resolve(path: &str, name: &str, exists: impl Fn(&str) -> bool) -> Option<String> {
+    let mut dir = path;
+    loop {
+        let candidate = match dir {
+            "" => format!("node_modules/{}", name),
+            dir => format!("{}/node_modules/{}", dir, name),
+        };
+        if exists(&candidate) {
+            return Some(candidate);
+        }
+        if dir.is_empty() {
+            return None;
+        }
+
+        dir = dir.rsplit_once('/').map_or("", |(parent, _)| parent);
+        dir = dir.strip_suffix("node_modules").unwrap_or(dir);
+        dir = dir.strip_suffix('/').unwrap_or(dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_package_lock() {
+        let input = r#"{
+  "name": "app",
+  "version": "1.0.0",
+  "lockfileVersion": 3,
+  "packages": {
+    "": {
+      "name": "app",
+      "version": "1.0.0",
+      "workspaces": ["packages/lib"],
+      "dependencies": {"@scope/a": "^1.0.0", "lib": "*"},
+      "devDependencies": {"b": "^2.0.0"},
+      "optionalDependencies": {"fsevents": "^2.0.0"}
+    },
+    "node_modules/@scope/a": {
+      "version": "1.2.0",
+      "dependencies": {"b": "^1.0.0"},
+      "peerDependencies": {"react": "*"}
+    },
+    "node_modules/@scope/a/node_modules/b": {"version": "1.5.0"},
+    "node_modules/b": {"version": "2.1.0", "dev": true},
+    "node_modules/lib": {"resolved": "packages/lib", "link": true},
+    "packages/lib": {"name": "lib", "version": "0.1.0", "dependencies": {"b": "^2.0.0"}}
+  }
+}"#;
+        let graph = parse_package_lock(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("app@1.0.0", "@scope/a@1.2.0", Some("prod")),
+                ("app@1.0.0", "lib@0.1.0", Some("prod")),
+                ("app@1.0.0", "b@2.1.0", Some("dev")),
+                ("@scope/a@1.2.0", "b@1.5.0", Some("prod")),
+                ("lib@0.1.0", "b@2.1.0", Some("prod")),
+            ]
+        );
+
+        assert!(parse_package_lock(r#"{"lockfileVersion": 1}"#).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! `pnpm-lock.yaml` importer (lockfile versions 5 to 9).
+//!
+//! Packages become `name@version` nodes and workspace projects are named
+//! after their directory, `.` being the root. Edges are labelled with the
+//! kind of the dependency: `prod`, `dev`, `optional` or `peer`.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::Graph;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Lockfile {
+    lockfile_version: serde_yaml::Value,
+    #[serde(default)]
+    importers: BTreeMap<String, Importer>,
+    // single project lockfiles before version 9 inline the root importer
+    #[serde(flatten)]
+    root: Importer,
+    #[serde(default)]
+    packages: BTreeMap<String, Package>,
+    #[serde(default)]
+    snapshots: BTreeMap<String, Package>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Importer {
+    #[serde(default)]
+    dependencies: BTreeMap<String, ImporterDependency>,
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, ImporterDependency>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, ImporterDependency>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ImporterDependency {
+    // lockfile version 5
+    Version(String),
+    // { specifier: ^1.0.0, version: 1.2.0 }
+    Resolved { version: String },
+}
+
+impl ImporterDependency {
+    fn version(&self) -> &str {
+        match self {
+            ImporterDependency::Version(version) => version,
+            ImporterDependency::Resolved { version } => version,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Package {
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Parses a `pnpm-lock.yaml` file.
+pub fn parse(input: &str) -> anyhow::Result<Graph> {
+    let lock: Lockfile = serde_yaml::from_str(input).context("invalid pnpm-lock.yaml")?;
+    let version = match &lock.lockfile_version {
+        serde_yaml::Value::String(version) => version.parse::<f64>().ok(),
+        version => version.as_f64(),
+    }
+    .context("invalid lockfileVersion")?;
+    let v5 = version < 6.0;
+
+    let mut graph = Graph::new();
+    let mut seen = HashSet::new();
+    let mut add_edge = |graph: &mut Graph, from: &str, to: String, kind: &str| {
+        if seen.insert((from.to_string(), to.clone(), kind.to_string())) {
+            super::add_edge(graph, from, &to, Some(kind.to_string()));
+        }
+    };
+
+    let root = ".".to_string();
+    let mut importers = lock.importers.iter().collect::<Vec<_>>();
+    if importers.is_empty() {
+        importers.push((&root, &lock.root));
+    }
+    for (path, importer) in importers {
+        graph.insert_node(path);
+        for (deps, kind) in [
+            (&importer.dependencies, "prod"),
+            (&importer.dev_dependencies, "dev"),
+            (&importer.optional_dependencies, "optional"),
+        ] {
+            for (name, dependency) in deps.iter() {
+                let to = match dependency.version().strip_prefix("link:") {
+                    Some(link) => join(path, link),
+                    None => dependency_node(name, dependency.version(), v5),
+                };
+                add_edge(&mut graph, path, to, kind);
+            }
+        }
+    }
+
+    // version 9 moved the resolved dependencies from packages to snapshots
+    let snapshots = match lock.snapshots.is_empty() {
+        true => &lock.packages,
+        false => &lock.snapshots,
+    };
+    let empty = Package::default();
+    for (key, snapshot) in snapshots.iter() {
+        let from = package_node(key, v5);
+        graph.insert_node(&from);
+        let package = lock
+            .packages
+            .get(key)
+            .or_else(|| lock.packages.get(strip_peers(key)))
+            .unwrap_or(&empty);
+
+        for (name, version) in snapshot.dependencies.iter() {
+            let kind = match package.peer_dependencies.contains_key(name) {
+                true => "peer",
+                false => "prod",
+            };
+            add_edge(&mut graph, &from, dependency_node(name, version, v5), kind);
+        }
+        for (name, version) in snapshot.optional_dependencies.iter() {
+            add_edge(
+                &mut graph,
+                &from,
+                dependency_node(name, version, v5),
+                "optional",
+            );
+        }
+    }
+    Ok(graph)
+}
+
+// "react-dom@18.2.0(react@18.2.0)" => "react-dom@18.2.0"
+fn strip_peers(key: &str) -> &str {
+    key.split('(').next().unwrap_or(key)
+}
+
+// package keys: "/name/1.0.0_peer@1.0.0" (v5), "/name@1.0.0(peer@1.0.0)" (v6)
+// or "name@1.0.0(peer@1.0.0)" (v9)
+fn package_node(key: &str, v5: bool) -> String {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    if !v5 {
+        return strip_peers(key).to_string();
+    }
+    match key.rsplit_once('/') {
+        Some((name, version)) => {
+            format!("{}@{}", name, version.split('_').next().unwrap_or(version))
+        }
+        None => key.to_string(),
+    }
+}
+
+fn dependency_node(name: &str, version: &str, v5: bool) -> String {
+    if version.starts_with('/') {
+        return package_node(version, v5);
+    }
+    let version = match v5 {
+        true => version.split('_').next().unwrap_or(version),
+        false => strip_peers(version),
+    };
+    // aliases resolve to another package, e.g. "string-width@4.2.3"
+    if version.char_indices().skip(1).any(|(_, c)| c == '@') {
+        return version.to_string();
+    }
+    format!("{}@{}", name, version)
+}
+
+// importer path joined with a relative `link:` target
+fn join(base: &str, link: &str) -> String {
+    let mut parts = base
+        .split('/')
+        .filter(|part| *part != "." && !part.is_empty())
+        .collect::<Vec<_>>();
+    for part in link.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    match parts.is_empty() {
+        true => ".".to_string(),
+        false => parts.join("/"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_v9() {
+        let input = r#"lockfileVersion: '9.0'
+
+settings:
+  autoInstallPeers: true
+
+importers:
+
+  .:
+    dependencies:
+      react-dom:
+        specifier: ^18.2.0
+        version: 18.2.0(react@18.2.0)
+      lib:
+        specifier: workspace:*
+        version: link:packages/lib
+    devDependencies:
+      string-width-cjs:
+        specifier: npm:string-width@^4.2.0
+        version: string-width@4.2.3
+
+  packages/lib:
+    dependencies:
+      react:
+        specifier: ^18.2.0
+        version: 18.2.0
+
+packages:
+
+  react-dom@18.2.0:
+    resolution: {integrity: sha512-x}
+    peerDependencies:
+      react: ^18.2.0
+
+  react@18.2.0:
+    resolution: {integrity: sha512-y}
+
+  string-width@4.2.3:
+    resolution: {integrity: sha512-z}
+
+snapshots:
+
+  react-dom@18.2.0(react@18.2.0):
+    dependencies:
+      react: 18.2.0
+
+  react@18.2.0: {}
+
+  string-width@4.2.3: {}
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (".", "packages/lib", Some("prod")),
+                (".", "react-dom@18.2.0", Some("prod")),
+                (".", "string-width@4.2.3", Some("dev")),
+                ("packages/lib", "react@18.2.0", Some("prod")),
+                ("react-dom@18.2.0", "react@18.2.0", Some("peer")),
+            ]
+        );
+        assert_eq!(graph.node_id("lib"), None);
+    }
+
+    #[test]
+    fn test_parse_v6() {
+        let input = r#"lockfileVersion: '6.0'
+
+dependencies:
+  react-dom:
+    specifier: ^18.2.0
+    version: 18.2.0(react@18.2.0)
+
+optionalDependencies:
+  fsevents:
+    specifier: ^2.3.0
+    version: 2.3.3
+
+packages:
+
+  /fsevents@2.3.3:
+    resolution: {integrity: sha512-x}
+    os: [darwin]
+    optional: true
+
+  /loose-envify@1.4.0:
+    resolution: {integrity: sha512-y}
+
+  /react-dom@18.2.0(react@18.2.0):
+    resolution: {integrity: sha512-z}
+    peerDependencies:
+      react: ^18.2.0
+    dependencies:
+      loose-envify: 1.4.0
+      react: 18.2.0
+
+  /react@18.2.0:
+    resolution: {integrity: sha512-w}
+    dependencies:
+      loose-envify: 1.4.0
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (".", "react-dom@18.2.0", Some("prod")),
+                (".", "fsevents@2.3.3", Some("optional")),
+                ("react-dom@18.2.0", "loose-envify@1.4.0", Some("prod")),
+                ("react-dom@18.2.0", "react@18.2.0", Some("peer")),
+                ("react@18.2.0", "loose-envify@1.4.0", Some("prod")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_v5() {
+        let input = r#"lockfileVersion: 5.4
+
+specifiers:
+  react-dom: ^18.2.0
+
+dependencies:
+  react-dom: 18.2.0_react@18.2.0
+
+packages:
+
+  /react-dom/18.2.0_react@18.2.0:
+    resolution: {integrity: sha512-z}
+    peerDependencies:
+      react: ^18.2.0
+    dependencies:
+      react: 18.2.0
+
+  /react/18.2.0:
+    resolution: {integrity: sha512-w}
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (".", "react-dom@18.2.0", Some("prod")),
+                ("react-dom@18.2.0", "react@18.2.0", Some("peer")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Python importers: `pipdeptree --json-tree`, `uv.lock` and `poetry.lock`.
+//!
+//! Packages become `name@version` nodes. Edges are labelled with the version
+//! specifier of the requirement, e.g. `>=8.1.3`, when the input records one.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::Graph;
+
+#[derive(Deserialize)]
+struct TreeNode {
+    package_name: String,
+    installed_version: String,
+    #[serde(default)]
+    required_version: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<TreeNode>,
+}
+
+/// Parses the JSON printed by `pipdeptree --json-tree`.
+///
+/// Requirements without a specifier, which pipdeptree prints as `Any`, have
+/// no label.
+pub fn parse_pipdeptree(input: &str) -> anyhow::Result<Graph> {
+    let roots: Vec<TreeNode> =
+        serde_json::from_str(input).context("invalid pipdeptree --json-tree output")?;
+
+    let mut graph = Graph::new();
+    // shared subtrees are repeated under every dependent
+    let mut seen = HashSet::new();
+    let mut stack = roots
+        .iter()
+        .map(|root| (tree_node(root), root))
+        .collect::<Vec<_>>();
+    stack.reverse();
+    while let Some((from, node)) = stack.pop() {
+        graph.insert_node(&from);
+        let mut children = Vec::new();
+        for dependency in node.dependencies.iter() {
+            let to = tree_node(dependency);
+            if !seen.insert((from.clone(), to.clone())) {
+                continue;
+            }
+            let label = dependency
+                .required_version
+                .as_deref()
+                .filter(|v| *v != "Any")
+                .map(str::to_string);
+            super::add_edge(&mut graph, &from, &to, label);
+            children.push((to, dependency));
+        }
+        stack.extend(children.into_iter().rev());
+    }
+    Ok(graph)
+}
+
+fn tree_node(node: &TreeNode) -> String {
+    format!("{}@{}", node.package_name, node.installed_version)
+}
+
+#[derive(Deserialize)]
+struct UvLock {
+    #[serde(default)]
+    package: Vec<UvPackage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct UvPackage {
+    name: String,
+    // missing for the virtual root of a workspace
+    version: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<UvDependency>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, Vec<UvDependency>>,
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, Vec<UvDependency>>,
+    #[serde(default)]
+    metadata: UvMetadata,
+}
+
+#[derive(Deserialize)]
+struct UvDependency {
+    name: String,
+    version: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+struct UvMetadata {
+    #[serde(default)]
+    requires_dist: Vec<UvRequirement>,
+    #[serde(default)]
+    requires_dev: BTreeMap<String, Vec<UvRequirement>>,
+}
+
+#[derive(Deserialize)]
+struct UvRequirement {
+    name: String,
+    specifier: Option<String>,
+}
+
+/// Parses a `uv.lock` file.
+///
+/// uv only records the requirements of workspace members, so edges leaving
+/// locked third-party packages have no label.
+pub fn parse_uv_lock(input: &str) -> anyhow::Result<Graph> {
+    let lock: UvLock = toml::from_str(input).context("invalid uv.lock")?;
+
+    let mut by_name: HashMap<&str, Vec<&UvPackage>> = HashMap::new();
+    for package in lock.package.iter() {
+        by_name.entry(&package.name).or_default().push(package);
+    }
+    let node = |package: &UvPackage| match &package.version {
+        Some(version) => format!("{}@{}", package.name, version),
+        None => package.name.clone(),
+    };
+
+    let mut graph = Graph::new();
+    for package in lock.package.iter() {
+        let from = node(package);
+        graph.insert_node(&from);
+
+        let specifiers = package
+            .metadata
+            .requires_dist
+            .iter()
+            .chain(package.metadata.requires_dev.values().flatten())
+            .filter_map(|r| Some((r.name.as_str(), r.specifier.as_deref()?)))
+            .collect::<HashMap<_, _>>();
+        let dependencies = package
+            .dependencies
+            .iter()
+            .chain(package.optional_dependencies.values().flatten())
+            .chain(package.dev_dependencies.values().flatten());
+        for dependency in dependencies {
+            let candidates = by_name
+                .get(dependency.name.as_str())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let to = candidates
+                .iter()
+                .find(|p| dependency.version.is_none() || p.version == dependency.version)
+                .with_context(|| {
+                    format!(
+                        "{} depends on unknown package \"{}\"",
+                        from, dependency.name
+                    )
+                })?;
+            let label = specifiers
+                .get(dependency.name.as_str())
+                .map(|s| s.to_string());
+            super::add_edge(&mut graph, &from, &node(to), label);
+        }
+    }
+    Ok(graph)
+}
+
+#[derive(Deserialize)]
+struct PoetryLock {
+    #[serde(default)]
+    package: Vec<PoetryPackage>,
+}
+
+#[derive(Deserialize)]
+struct PoetryPackage {
+    name: String,
+    version: String,
+    #[serde(default)]
+    dependencies: BTreeMap<String, PoetryDependency>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PoetryDependency {
+    // click = ">=8.1.3"
+    Specifier(String),
+    // click = { version = ">=8.1.3", markers = "..." }
+    Table(PoetryConstraint),
+    // one constraint per set of markers
+    Multiple(Vec<PoetryConstraint>),
+}
+
+#[derive(Deserialize)]
+struct PoetryConstraint {
+    version: Option<String>,
+}
+
+/// Parses a `poetry.lock` file.
+///
+/// A dependency with several constraints, one per set of markers, gets one
+/// edge per constraint.
+pub fn parse_poetry_lock(input: &str) -> anyhow::Result<Graph> {
+    let lock: PoetryLock = toml::from_str(input).context("invalid poetry.lock")?;
+
+    let versions = lock
+        .package
+        .iter()
+        .map(|p| (normalize(&p.name), p))
+        .collect::<HashMap<_, _>>();
+
+    let mut graph = Graph::new();
+    for package in lock.package.iter() {
+        let from = format!("{}@{}", package.name, package.version);
+        graph.insert_node(&from);
+
+        for (name, dependency) in package.dependencies.iter() {
+            // dependencies excluded by their markers are not locked
+            let to = match versions.get(&normalize(name)) {
+                Some(p) => format!("{}@{}", p.name, p.version),
+                None => name.clone(),
+            };
+            let specifiers = match dependency {
+                PoetryDependency::Specifier(s) => vec![Some(s.as_str())],
+                PoetryDependency::Table(c) => vec![c.version.as_deref()],
+                PoetryDependency::Multiple(cs) => cs.iter().map(|c| c.version.as_deref()).collect(),
+            };
+            for specifier in specifiers {
+                let label = specifier.filter(|s| *s != "*").map(str::to_string);
+                super::add_edge(&mut graph, &from, &to, label);
+            }
+        }
+    }
+    Ok(graph)
+}
+
+// PEP 503 normalized name
+fn normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '-' | '_' | '.' => {
+                if !normalized.ends_with('-') {
+                    normalized.push('-');
+                }
+            }
+            c => normalized.push(c.to_ascii_lowercase()),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_pipdeptree() {
+        let input = r#"[
+            {
+                "key": "flask",
+                "package_name": "Flask",
+                "installed_version": "3.0.0",
+                "required_version": "3.0.0",
+                "dependencies": [
+                    {
+                        "key": "click",
+                        "package_name": "click",
+                        "installed_version": "8.1.7",
+                        "required_version": ">=8.1.3",
+                        "dependencies": []
+                    },
+                    {
+                        "key": "jinja2",
+                        "package_name": "Jinja2",
+                        "installed_version": "3.1.2",
+                        "required_version": ">=3.1.2",
+                        "dependencies": [
+                            {
+                                "key": "markupsafe",
+                                "package_name": "MarkupSafe",
+                                "installed_version": "2.1.3",
+                                "required_version": "Any",
+                                "dependencies": []
+                            }
+                        ]
+                    }
+                ]
+            },
+            {
+                "key": "jinja2",
+                "package_name": "Jinja2",
+                "installed_version": "3.1.2",
+                "required_version": "3.1.2",
+                "dependencies": [
+                    {
+                        "key": "markupsafe",
+                        "package_name": "MarkupSafe",
+                        "installed_version": "2.1.3",
+                        "required_version": "Any",
+                        "dependencies": []
+                    }
+                ]
+            }
+        ]"#;
+        let graph = parse_pipdeptree(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("Flask@3.0.0", "click@8.1.7", Some(">=8.1.3")),
+                ("Flask@3.0.0", "Jinja2@3.1.2", Some(">=3.1.2")),
+                ("Jinja2@3.1.2", "MarkupSafe@2.1.3", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_uv_lock() {
+        let input = r#"
+version = 1
+requires-python = ">=3.12"
+
+[[package]]
+name = "app"
+version = "0.1.0"
+source = { editable = "." }
+dependencies = [
+    { name = "flask" },
+]
+
+[package.dev-dependencies]
+dev = [
+    { name = "pytest" },
+]
+
+[package.metadata]
+requires-dist = [{ name = "flask", specifier = ">=3" }]
+
+[package.metadata.requires-dev]
+dev = [{ name = "pytest", specifier = ">=8" }]
+
+[[package]]
+name = "click"
+version = "8.1.7"
+source = { registry = "https://pypi.org/simple" }
+
+[[package]]
+name = "flask"
+version = "3.0.0"
+source = { registry = "https://pypi.org/simple" }
+dependencies = [
+    { name = "click" },
+]
+
+[[package]]
+name = "pytest"
+version = "8.0.0"
+source = { registry = "https://pypi.org/simple" }
+"#;
+        let graph = parse_uv_lock(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("app@0.1.0", "flask@3.0.0", Some(">=3")),
+                ("app@0.1.0", "pytest@8.0.0", Some(">=8")),
+                ("flask@3.0.0", "click@8.1.7", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_poetry_lock() {
+        let input = r#"
+[[package]]
+name = "click"
+version = "8.1.7"
+optional = false
+python-versions = ">=3.7"
+files = []
+
+[package.dependencies]
+colorama = {version = "*", markers = "platform_system == \"Windows\""}
+
+[[package]]
+name = "flask"
+version = "3.0.0"
+optional = false
+python-versions = ">=3.8"
+files = []
+
+[package.dependencies]
+click = ">=8.1.3"
+Jinja2 = ">=3.1.2"
+numpy = [
+    {version = ">=1.22", markers = "python_version < \"3.12\""},
+    {version = ">=1.26", markers = "python_version >= \"3.12\""},
+]
+
+[[package]]
+name = "jinja2"
+version = "3.1.2"
+optional = false
+python-versions = ">=3.7"
+files = []
+
+[metadata]
+lock-version = "2.0"
+"#;
+        let graph = parse_poetry_lock(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("click@8.1.7", "colorama", None),
+                ("flask@3.0.0", "jinja2@3.1.2", Some(">=3.1.2")),
+                ("flask@3.0.0", "click@8.1.7", Some(">=8.1.3")),
+                ("flask@3.0.0", "numpy", Some(">=1.22")),
+                ("flask@3.0.0", "numpy", Some(">=1.26")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! `yarn.lock` importer for both the classic (v1) and the YAML based
+//! (yarn 2 and later) lockfile formats.
+//!
+//! Packages become `name@version` nodes. Edges are labelled with the kind of
+//! the dependency: `prod`, `optional` or `peer`. The lockfile does not know
+//! which dependencies of the project are dev dependencies.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::Graph;
+
+#[derive(Default)]
+struct Entry {
+    version: String,
+    // (name, range, kind)
+    dependencies: Vec<(String, String, &'static str)>,
+}
+
+/// Parses a `yarn.lock` file.
+pub fn parse(input: &str) -> anyhow::Result<Graph> {
+    let entries = if input.lines().any(|line| line.starts_with("__metadata:")) {
+        parse_berry(input)?
+    } else {
+        parse_classic(input)?
+    };
+
+    // every entry is keyed by all the `name@range` descriptors it satisfies
+    let mut nodes = HashMap::new();
+    for (descriptors, entry) in entries.iter() {
+        for descriptor in descriptors.iter() {
+            let name = package_name(descriptor);
+            nodes.insert(descriptor.as_str(), format!("{}@{}", name, entry.version));
+        }
+    }
+
+    let mut graph = Graph::new();
+    for (descriptors, entry) in entries.iter() {
+        let from = &nodes[descriptors[0].as_str()];
+        graph.insert_node(from);
+        for (name, range, kind) in entry.dependencies.iter() {
+            let descriptor = format!("{}@{}", name, range);
+            // yarn 2+ adds the default `npm:` protocol to the descriptors
+            let to = nodes
+                .get(descriptor.as_str())
+                .or_else(|| nodes.get(format!("{}@npm:{}", name, range).as_str()));
+            match to {
+                Some(to) => super::add_edge(&mut graph, from, to, Some(kind.to_string())),
+                None => log::debug!("{} {} dependency {} is not locked", from, kind, descriptor),
+            }
+        }
+    }
+    Ok(graph)
+}
+
+// "@scope/name@^1.0.0" => "@scope/name"
+fn package_name(descriptor: &str) -> &str {
+    match descriptor.char_indices().skip(1).find(|&(_, c)| c == '@') {
+        Some((idx, _)) => &descriptor[..idx],
+        None => descriptor,
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+fn parse_classic(input: &str) -> anyhow::Result<Vec<(Vec<String>, Entry)>> {
+    let mut entries: Vec<(Vec<String>, Entry)> = Vec::new();
+    let mut section = None;
+    for (idx, line) in input.lines().enumerate() {
+        let content = line.trim();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let error = || format!("error parsing line {}: \"{}\"", idx + 1, line);
+
+        if indent == 0 {
+            // "a@^1.0.0", a@^1.1.0:
+            let descriptors = content
+                .strip_suffix(':')
+                .with_context(error)?
+                .split(',')
+                .map(|s| unquote(s).to_string())
+                .collect();
+            entries.push((descriptors, Entry::default()));
+            section = None;
+            continue;
+        }
+
+        let (_, entry) = entries.last_mut().with_context(error)?;
+        if indent == 2 {
+            section = match content {
+                "dependencies:" => Some("prod"),
+                "optionalDependencies:" => Some("optional"),
+                "peerDependencies:" => Some("peer"),
+                _ => None,
+            };
+            if let Some(version) = content.strip_prefix("version ") {
+                entry.version = unquote(version).to_string();
+            }
+        } else if let Some(kind) = section {
+            // "@scope/name" "^1.0.0"
+            let (name, range) = match content.strip_prefix('"') {
+                Some(rest) => rest.split_once("\" ").with_context(error)?,
+                None => content.split_once(' ').with_context(error)?,
+            };
+            entry
+                .dependencies
+                .push((name.to_string(), unquote(range).to_string(), kind));
+        }
+    }
+
+    // optional dependencies are listed in both sections
+    for (_, entry) in entries.iter_mut() {
+        let optional = entry
+            .dependencies
+            .iter()
+            .filter(|(_, _, kind)| *kind == "optional")
+            .map(|(name, _, _)| name.clone())
+            .collect::<Vec<_>>();
+        entry
+            .dependencies
+            .retain(|(name, _, kind)| *kind != "prod" || !optional.contains(name));
+    }
+    Ok(entries)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BerryEntry {
+    version: serde_yaml::Value,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    dependencies_meta: BTreeMap<String, DependencyMeta>,
+}
+
+#[derive(Deserialize)]
+struct DependencyMeta {
+    #[serde(default)]
+    optional: bool,
+}
+
+fn parse_berry(input: &str) -> anyhow::Result<Vec<(Vec<String>, Entry)>> {
+    let mut lock: BTreeMap<String, serde_yaml::Value> =
+        serde_yaml::from_str(input).context("invalid yarn.lock")?;
+    lock.remove("__metadata");
+
+    let mut entries = Vec::new();
+    for (key, value) in lock {
+        let berry: BerryEntry = serde_yaml::from_value(value)
+            .with_context(|| format!("invalid yarn.lock entry \"{}\"", key))?;
+        let version = match berry.version {
+            serde_yaml::Value::String(version) => version,
+            version => serde_yaml::to_string(&version)?.trim().to_string(),
+        };
+
+        let mut entry = Entry {
+            version,
+            dependencies: Vec::new(),
+        };
+        for (name, range) in berry.dependencies {
+            let optional = berry
+                .dependencies_meta
+                .get(&name)
+                .is_some_and(|m| m.optional);
+            let kind = if optional { "optional" } else { "prod" };
+            entry.dependencies.push((name, range, kind));
+        }
+        for (name, range) in berry.peer_dependencies {
+            entry.dependencies.push((name, range, "peer"));
+        }
+
+        let descriptors = key.split(',').map(|s| s.trim().to_string()).collect();
+        entries.push((descriptors, entry));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_classic() {
+        let input = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/code-frame@^7.0.0", "@babel/code-frame@^7.10.4":
+  version "7.12.13"
+  resolved "https://registry.yarnpkg.com/@babel/code-frame/-/code-frame-7.12.13.tgz"
+  dependencies:
+    "@babel/highlight" "^7.12.13"
+    chokidar "^3.0.0"
+  optionalDependencies:
+    chokidar "^3.0.0"
+
+"@babel/highlight@^7.12.13":
+  version "7.13.10"
+
+chokidar@^3.0.0:
+  version "3.5.1"
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (
+                    "@babel/code-frame@7.12.13",
+                    "@babel/highlight@7.13.10",
+                    Some("prod")
+                ),
+                (
+                    "@babel/code-frame@7.12.13",
+                    "chokidar@3.5.1",
+                    Some("optional")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_berry() {
+        let input = r#"# This file is generated by running "yarn install" inside your project.
+
+__metadata:
+  version: 6
+  cacheKey: 8
+
+"@babel/code-frame@npm:^7.0.0":
+  version: 7.12.13
+  resolution: "@babel/code-frame@npm:7.12.13"
+  dependencies:
+    "@babel/highlight": ^7.12.13
+    fsevents: ^2.0.0
+  dependenciesMeta:
+    fsevents:
+      optional: true
+  peerDependencies:
+    react: "*"
+  languageName: node
+  linkType: hard
+
+"@babel/highlight@npm:^7.12.13":
+  version: 7.13.10
+  resolution: "@babel/highlight@npm:7.13.10"
+  languageName: node
+  linkType: hard
+
+"app@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "app@workspace:."
+  dependencies:
+    "@babel/code-frame": ^7.0.0
+  languageName: unknown
+  linkType: soft
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                (
+                    "@babel/code-frame@7.12.13",
+                    "@babel/highlight@7.13.10",
+                    Some("prod")
+                ),
+                (
+                    "app@0.0.0-use.local",
+                    "@babel/code-frame@7.12.13",
+                    Some("prod")
+                ),
+            ]
+        );
+    }
+}
@@ -1,22 +1,38 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem;
 
+pub mod algo;
 pub mod cypher;
+pub mod diff;
 pub mod dot;
 pub mod fileutil;
 pub mod graphviz;
+pub mod import;
+pub mod scan;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Edge {
     pub from: NodeId,
     pub to: NodeId,
     pub label: Option<String>,
+    pub attributes: Attributes,
 }
 
 pub type NodeId = usize;
+pub type EdgeId = usize;
+
+/// Graphviz attributes, e.g. `color` => `red`.
+pub type Attributes = BTreeMap<String, String>;
 
 pub struct Graph {
     node_arena: Arena,
     edges: Vec<Edge>,
+
+    node_attributes: Vec<Attributes>,
+
+    // per node ids of outgoing and incoming edges
+    out_edges: Vec<Vec<EdgeId>>,
+    in_edges: Vec<Vec<EdgeId>>,
 }
 
 impl Default for Graph {
@@ -30,16 +46,193 @@ impl Graph {
         Graph {
             node_arena: Arena::new(),
             edges: Vec::new(),
+            node_attributes: Vec::new(),
+            out_edges: Vec::new(),
+            in_edges: Vec::new(),
         }
     }
 
     pub fn insert_node(&mut self, node: &str) -> NodeId {
-        self.node_arena.insert(node.to_string())
+        let id = self.node_arena.insert(node.to_string());
+        if id == self.out_edges.len() {
+            self.node_attributes.push(Attributes::new());
+            self.out_edges.push(Vec::new());
+            self.in_edges.push(Vec::new());
+        }
+        id
     }
 
     pub fn add_edge(&mut self, edge: Edge) {
+        let id = self.edges.len();
+        self.out_edges[edge.from].push(id);
+        self.in_edges[edge.to].push(id);
         self.edges.push(edge);
     }
+
+    pub fn node_count(&self) -> usize {
+        self.node_arena.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns all nodes with their ids, in id order.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &str)> + '_ {
+        self.node_arena.nodes.iter().map(|s| s.as_str()).enumerate()
+    }
+
+    /// Returns all edges, in id order.
+    pub fn edges(&self) -> impl Iterator<Item = &Edge> + '_ {
+        self.edges.iter()
+    }
+
+    pub fn edge(&self, id: EdgeId) -> Option<&Edge> {
+        self.edges.get(id)
+    }
+
+    pub fn edge_mut(&mut self, id: EdgeId) -> Option<&mut Edge> {
+        self.edges.get_mut(id)
+    }
+
+    /// Removes the given edges. Ids of the remaining edges are shifted down.
+    pub fn remove_edges(&mut self, ids: &[EdgeId]) {
+        let ids = ids.iter().collect::<HashSet<_>>();
+        let mut id = 0;
+        self.edges.retain(|_| {
+            let keep = !ids.contains(&id);
+            id += 1;
+            keep
+        });
+
+        self.rebuild_edge_index();
+    }
+
+    /// Merges edges with the same endpoints into the first of them and
+    /// returns, for every remaining edge, how many edges it stands for.
+    ///
+    /// The merged edge keeps the attributes of the first edge; its label is
+    /// the distinct labels of all merged edges in input order, joined by ", ".
+    pub fn merge_parallel_edges(&mut self) -> Vec<usize> {
+        let mut merged: Vec<Edge> = Vec::new();
+        let mut counts = Vec::new();
+        let mut labels: Vec<Vec<String>> = Vec::new();
+        let mut ids = HashMap::new();
+        for edge in mem::take(&mut self.edges) {
+            let id = *ids.entry((edge.from, edge.to)).or_insert_with(|| {
+                merged.push(Edge {
+                    label: None,
+                    ..edge.clone()
+                });
+                counts.push(0);
+                labels.push(Vec::new());
+                merged.len() - 1
+            });
+            counts[id] += 1;
+            if let Some(label) = edge.label {
+                if !labels[id].contains(&label) {
+                    labels[id].push(label);
+                }
+            }
+        }
+        for (edge, labels) in merged.iter_mut().zip(labels) {
+            if !labels.is_empty() {
+                edge.label = Some(labels.join(", "));
+            }
+        }
+
+        self.edges = merged;
+        self.rebuild_edge_index();
+        counts
+    }
+
+    /// Flips the direction of every edge.
+    pub fn reverse(&mut self) {
+        for edge in self.edges.iter_mut() {
+            mem::swap(&mut edge.from, &mut edge.to);
+        }
+        mem::swap(&mut self.out_edges, &mut self.in_edges);
+    }
+
+    fn rebuild_edge_index(&mut self) {
+        self.out_edges.iter_mut().for_each(Vec::clear);
+        self.in_edges.iter_mut().for_each(Vec::clear);
+        for (id, edge) in self.edges.iter().enumerate() {
+            self.out_edges[edge.from].push(id);
+            self.in_edges[edge.to].push(id);
+        }
+    }
+
+    pub fn node_id(&self, name: &str) -> Option<NodeId> {
+        self.node_arena.lookup(name)
+    }
+
+    pub fn node_name(&self, id: NodeId) -> Option<&str> {
+        self.node_arena.get(id)
+    }
+
+    pub fn node_attributes(&self, id: NodeId) -> &Attributes {
+        &self.node_attributes[id]
+    }
+
+    pub fn node_attributes_mut(&mut self, id: NodeId) -> &mut Attributes {
+        &mut self.node_attributes[id]
+    }
+
+    /// Returns the targets of the edges leaving `id`, once per edge.
+    ///
+    /// Panics if `id` is not a node of this graph; the same holds for the
+    /// other per-node lookups below.
+    pub fn out_neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.out_edges[id].iter().map(|&edge| self.edges[edge].to)
+    }
+
+    /// Returns the sources of the edges entering `id`, once per edge.
+    pub fn in_neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.in_edges[id].iter().map(|&edge| self.edges[edge].from)
+    }
+
+    /// Returns the ids of the edges leaving `id`.
+    pub fn out_edges(&self, id: NodeId) -> &[EdgeId] {
+        &self.out_edges[id]
+    }
+
+    /// Returns the ids of the edges entering `id`.
+    pub fn in_edges(&self, id: NodeId) -> &[EdgeId] {
+        &self.in_edges[id]
+    }
+
+    pub fn out_degree(&self, id: NodeId) -> usize {
+        self.out_edges[id].len()
+    }
+
+    pub fn in_degree(&self, id: NodeId) -> usize {
+        self.in_edges[id].len()
+    }
+
+    /// Returns a new graph with only the given nodes and the edges between
+    /// them. Nodes are renumbered in their original order.
+    pub fn subgraph(&self, nodes: &HashSet<NodeId>) -> Graph {
+        let mut graph = Graph::new();
+        let mut ids = HashMap::new();
+        for (id, name) in self.node_arena.nodes.iter().enumerate() {
+            if nodes.contains(&id) {
+                let new_id = graph.insert_node(name);
+                graph.node_attributes[new_id] = self.node_attributes[id].clone();
+                ids.insert(id, new_id);
+            }
+        }
+        for edge in self.edges.iter() {
+            if let (Some(&from), Some(&to)) = (ids.get(&edge.from), ids.get(&edge.to)) {
+                graph.add_edge(Edge {
+                    from,
+                    to,
+                    ..edge.clone()
+                });
+            }
+        }
+        graph
+    }
 }
 
 #[derive(Debug)]
@@ -64,10 +257,17 @@ impl Arena {
         })
     }
 
-    #[test]
     fn get(&self, id: NodeId) -> Option<&str> {
         self.nodes.get(id).map(|s| s.as_str())
     }
+
+    fn lookup(&self, node: &str) -> Option<NodeId> {
+        self.inverted_index.get(node).copied()
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
 }
 
 #[cfg(test)]
@@ -89,5 +289,73 @@ mod tests {
 
         assert_eq!(arena.insert("d".to_string()), 3);
         assert_eq!(arena.get(3), Some("d"));
+        assert_eq!(arena.lookup("d"), Some(3));
+        assert_eq!(arena.lookup("e"), None);
+    }
+
+    #[test]
+    fn test_graph() {
+        let mut graph = Graph::new();
+        let a = graph.insert_node("a");
+        let b = graph.insert_node("b");
+        let c = graph.insert_node("c");
+        for (from, to) in [(a, b), (a, c), (b, c), (a, b)] {
+            graph.add_edge(Edge {
+                from,
+                to,
+                label: None,
+                attributes: Attributes::new(),
+            });
+        }
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(
+            graph.nodes().collect::<Vec<_>>(),
+            vec![(0, "a"), (1, "b"), (2, "c")]
+        );
+        assert_eq!(graph.node_id("b"), Some(b));
+        assert_eq!(graph.node_name(c), Some("c"));
+        assert_eq!(graph.node_name(3), None);
+
+        assert_eq!(graph.out_neighbors(a).collect::<Vec<_>>(), vec![b, c, b]);
+        assert_eq!(graph.in_neighbors(c).collect::<Vec<_>>(), vec![a, b]);
+        assert_eq!(graph.out_degree(a), 3);
+        assert_eq!(graph.in_degree(b), 2);
+        assert_eq!(graph.in_degree(a), 0);
+
+        graph.remove_edges(&[0, 3]);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.out_edges(a), &[0]);
+        assert_eq!(graph.edge(0).map(|edge| edge.to), Some(c));
+        assert_eq!(graph.in_degree(b), 0);
+    }
+
+    #[test]
+    fn test_merge_parallel_edges() {
+        let mut graph = Graph::new();
+        let a = graph.insert_node("a");
+        let b = graph.insert_node("b");
+        let c = graph.insert_node("c");
+        for (from, to, label) in [
+            (a, b, Some("x")),
+            (a, c, None),
+            (a, b, Some("y")),
+            (a, b, None),
+            (a, b, Some("x")),
+        ] {
+            graph.add_edge(Edge {
+                from,
+                to,
+                label: label.map(|s| s.to_string()),
+                attributes: Attributes::new(),
+            });
+        }
+
+        assert_eq!(graph.merge_parallel_edges(), vec![4, 1]);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.edge(0).unwrap().label.as_deref(), Some("x, y"));
+        assert_eq!(graph.edge(1).unwrap().label, None);
+        assert_eq!(graph.out_neighbors(a).collect::<Vec<_>>(), vec![b, c]);
     }
 }
@@ -1,12 +1,15 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
-use std::io::{self, BufRead, Read, Write};
-use std::path::Path;
-use std::{error, fs, mem};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::{error, fs};
 
 use anyhow::Context;
+use clap::builder::{PossibleValue, PossibleValuesParser, RangedU64ValueParser};
 use clap::{Args, Parser, Subcommand};
-use deptree::{cypher, dot, fileutil, graphviz, Edge, Graph};
+use deptree::{
+    algo, cypher, diff, dot, fileutil, graphviz, import, scan, Attributes, Edge, Graph, NodeId,
+};
 use itertools::Itertools;
 use kuzu::{Connection, Database, SystemConfig};
 
@@ -70,6 +73,50 @@ impl Display for Shape {
     }
 }
 
+#[derive(Debug, Clone, clap::ValueEnum, Default)]
+enum Direction {
+    #[default]
+    Down,
+    Up,
+    Both,
+}
+
+impl From<&Direction> for algo::Direction {
+    fn from(direction: &Direction) -> Self {
+        match direction {
+            Direction::Down => algo::Direction::Down,
+            Direction::Up => algo::Direction::Up,
+            Direction::Both => algo::Direction::Both,
+        }
+    }
+}
+
+#[derive(Debug, Clone, clap::ValueEnum, Default)]
+enum Multiplicity {
+    Penwidth,
+    Label,
+    #[default]
+    Both,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum, Default)]
+enum SystemHeaders {
+    #[default]
+    Keep,
+    Group,
+    Drop,
+}
+
+impl From<&SystemHeaders> for scan::cpp::SystemHeaders {
+    fn from(system_headers: &SystemHeaders) -> Self {
+        match system_headers {
+            SystemHeaders::Keep => scan::cpp::SystemHeaders::Keep,
+            SystemHeaders::Group => scan::cpp::SystemHeaders::Group,
+            SystemHeaders::Drop => scan::cpp::SystemHeaders::Drop,
+        }
+    }
+}
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
@@ -81,11 +128,31 @@ struct DepTreeCommands {
 #[derive(Subcommand)]
 enum Commands {
     Graph(GraphCommand),
+    Check(CheckCommand),
+    Order(OrderCommand),
+    Path(PathCommand),
+    Diff(DiffCommand),
+    Scan(ScanCommand),
     Kuzu(KuzuCommand),
 }
 
+// `--from` values: the registered formats and "auto"
+fn input_formats() -> PossibleValuesParser {
+    let registry = import::Registry::default();
+    let formats = registry
+        .importers()
+        .map(|importer| PossibleValue::new(importer.name()).help(importer.description()))
+        .collect::<Vec<_>>();
+    let auto = PossibleValue::new("auto").help("Guess the format from the input, edges if unsure");
+    PossibleValuesParser::new(formats.into_iter().chain([auto]))
+}
+
 #[derive(Args, Debug)]
-struct GraphCommand {
+struct InputArgs {
+    /// Format of the input
+    #[arg(long, default_value = "edges", value_parser = input_formats())]
+    from: String,
+
     #[arg(long)]
     #[clap(default_value = "->")]
     edge_delimiter: String,
@@ -95,17 +162,56 @@ struct GraphCommand {
     label_delimiter: String,
 
     #[arg(short, long)]
-    #[clap(default_value = "graph.svg")]
-    output: String,
+    #[clap(default_value_t = false)]
+    reverse: bool,
 
+    /// Merge all versions of a module into one node (go-mod-graph)
+    #[arg(long)]
+    #[clap(default_value_t = false)]
+    strip_versions: bool,
+}
+
+impl InputArgs {
+    fn build_graph(&self) -> anyhow::Result<Graph> {
+        let input = read_input().context("failed to read input")?;
+        self.import(&input)
+    }
+
+    fn build_graph_from_file(&self, path: &str) -> anyhow::Result<Graph> {
+        let input = fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?;
+        self.import(&input)
+            .with_context(|| format!("failed to parse {}", path))
+    }
+
+    fn import(&self, input: &str) -> anyhow::Result<Graph> {
+        let registry = import::Registry::default();
+        let format = match self.from.as_str() {
+            "auto" => registry
+                .detect(input)
+                .map_or("edges", |importer| importer.name()),
+            format => format,
+        };
+        log::debug!("reading {} input", format);
+
+        let options = import::ImportOptions {
+            edge_delimiter: self.edge_delimiter.clone(),
+            label_delimiter: self.label_delimiter.clone(),
+            strip_versions: self.strip_versions,
+        };
+        let mut graph = registry.import(format, &mut input.as_bytes(), &options)?;
+        if self.reverse {
+            graph.reverse();
+        }
+        Ok(graph)
+    }
+}
+
+#[derive(Args, Debug)]
+struct RenderArgs {
     #[arg(short, long)]
     #[clap(default_value = "G")]
     graph_name: String,
 
-    #[arg(short, long)]
-    #[clap(default_value_t = false)]
-    reverse: bool,
-
     #[arg(short, long, value_enum, default_value_t = Layout::default())]
     layout: Layout,
 
@@ -113,26 +219,14 @@ struct GraphCommand {
     node_shape: Shape,
 }
 
-impl GraphCommand {
-    fn run(&self) -> anyhow::Result<()> {
-        let inputs = read_input().context("failed to read input")?;
-
-        let mut graph = Graph::new();
-        for (idx, input) in inputs.iter().enumerate() {
-            let (from, to, label) = parse_line(input, &self.edge_delimiter, &self.label_delimiter)
-                .with_context(|| format!("error parsing line {}: \"{}\"", idx + 1, input))?;
-            let mut from_id = graph.insert_node(from);
-            let mut to_id = graph.insert_node(to);
-            if self.reverse {
-                mem::swap(&mut from_id, &mut to_id);
-            }
-
-            let edge = Edge {
-                from: from_id,
-                to: to_id,
-                label: label.map(|s| s.to_string()),
-            };
-            graph.add_edge(edge);
+impl RenderArgs {
+    fn render(&self, graph: &Graph, output: &str) -> anyhow::Result<()> {
+        if fileutil::get_extension(output) == Some("graphml") {
+            let mut file =
+                fs::File::create(output).with_context(|| format!("failed to create {}", output))?;
+            import::graphml::write(graph, &self.graph_name, &mut file)?;
+            println!("wrote {}", output);
+            return Ok(());
         }
 
         let mut graph_config = graphviz::Config {
@@ -149,14 +243,464 @@ impl GraphCommand {
             filename.as_os_str().to_string_lossy()
         );
 
-        dot::write(&graph_config, &graph, &mut dot_file)
+        dot::write(&graph_config, graph, &mut dot_file)
             .context("failed to write temporary dot file")?;
-        dot::compile(&self.output, &filename).context("failed to compile temporary dot file")?;
-        println!("wrote {}", self.output);
+        dot::compile(output, &filename).context("failed to compile temporary dot file")?;
+        println!("wrote {}", output);
+        Ok(())
+    }
+}
+
+#[derive(Args, Debug)]
+struct GraphCommand {
+    #[command(flatten)]
+    input: InputArgs,
+
+    #[arg(short, long)]
+    #[clap(default_value = "graph.svg")]
+    output: String,
+
+    #[command(flatten)]
+    render: RenderArgs,
+
+    /// Merge edges with the same endpoints into one edge
+    #[arg(long)]
+    #[clap(default_value_t = false)]
+    merge_edges: bool,
+
+    /// How --merge-edges shows the number of merged edges
+    #[arg(long, value_enum, default_value_t = Multiplicity::default(), requires = "merge_edges")]
+    multiplicity: Multiplicity,
+
+    /// Only render the nodes around this node (can be repeated)
+    #[arg(long)]
+    root: Vec<String>,
+
+    /// Maximum number of edges between a --root and a rendered node
+    #[arg(long, requires = "root")]
+    depth: Option<usize>,
+
+    /// Render the nodes reachable from (down) or reaching (up) a --root
+    #[arg(long, value_enum, default_value_t = Direction::default(), requires = "root")]
+    direction: Direction,
+
+    /// Remove edges that are implied by a longer path
+    #[arg(long)]
+    #[clap(default_value_t = false)]
+    transitive_reduction: bool,
+
+    /// Draw the edges removed by --transitive-reduction faintly instead of dropping them
+    #[arg(long, requires = "transitive_reduction")]
+    #[clap(default_value_t = false)]
+    show_reduced: bool,
+}
+
+impl GraphCommand {
+    fn run(&self) -> anyhow::Result<()> {
+        let mut graph = self.input.build_graph()?;
+
+        if self.merge_edges {
+            let counts = graph.merge_parallel_edges();
+            for (id, count) in counts.into_iter().enumerate() {
+                if count > 1 {
+                    show_multiplicity(graph.edge_mut(id).unwrap(), count, &self.multiplicity);
+                }
+            }
+        }
+
+        if !self.root.is_empty() {
+            let roots = self
+                .root
+                .iter()
+                .map(|name| find_node(&graph, name))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let nodes = algo::neighborhood(&graph, &roots, self.depth, (&self.direction).into());
+            graph = graph.subgraph(&nodes);
+        }
+
+        if self.transitive_reduction {
+            let redundant = algo::transitive_reduction(&graph);
+            log::debug!("{} redundant edge(s)", redundant.len());
+            if self.show_reduced {
+                for &id in redundant.iter() {
+                    let attributes = &mut graph.edge_mut(id).unwrap().attributes;
+                    attributes.insert("style".to_string(), "dashed".to_string());
+                    attributes.insert("color".to_string(), "gray".to_string());
+                    attributes.insert("constraint".to_string(), "false".to_string());
+                }
+            } else {
+                graph.remove_edges(&redundant);
+            }
+        }
+
+        self.render.render(&graph, &self.output)
+    }
+}
+
+/// Fail when the input contains dependency cycles
+#[derive(Args, Debug)]
+struct CheckCommand {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Maximum number of cycles reported; the check fails on any cycle
+    #[arg(long, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    #[clap(default_value_t = 100)]
+    limit: usize,
+}
+
+impl CheckCommand {
+    fn run(&self) -> anyhow::Result<()> {
+        let graph = self.input.build_graph()?;
+        self.check(&graph)
+    }
+
+    fn check(&self, graph: &Graph) -> anyhow::Result<()> {
+        // the limit only caps the listing, the components decide the result
+        if algo::cyclic_components(graph).is_empty() {
+            println!("no cycles found");
+            return Ok(());
+        }
+        let cycles = algo::cycles(graph, self.limit);
+
+        let name = |id| graph.node_name(id).unwrap();
+        for cycle in cycles.iter() {
+            let path = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|&id| name(id))
+                .join(" -> ");
+            println!("cycle: {}", path);
+        }
+        if cycles.len() == self.limit {
+            return Err(anyhow::anyhow!(
+                "found at least {} cycle(s), raise --limit to see more",
+                cycles.len()
+            ));
+        }
+        Err(anyhow::anyhow!("found {} cycle(s)", cycles.len()))
+    }
+}
+
+/// Print the nodes in topological order, each node before the nodes it points to
+#[derive(Args, Debug)]
+struct OrderCommand {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Print one level per line; nodes on the same level do not depend on each other
+    #[arg(long)]
+    #[clap(default_value_t = false)]
+    group: bool,
+}
+
+impl OrderCommand {
+    fn run(&self) -> anyhow::Result<()> {
+        let graph = self.input.build_graph()?;
+        let name = |id| graph.node_name(id).unwrap();
+
+        let levels = match algo::topological_levels(&graph) {
+            Ok(levels) => levels,
+            Err(components) => {
+                for component in components.iter() {
+                    let nodes = component.iter().map(|&id| name(id)).join(", ");
+                    println!("cycle: {}", nodes);
+                }
+                return Err(anyhow::anyhow!(
+                    "no topological order: found {} cycle(s)",
+                    components.len()
+                ));
+            }
+        };
+
+        for level in levels.iter() {
+            if self.group {
+                println!("{}", level.iter().map(|&id| name(id)).join(" "));
+            } else {
+                for &id in level.iter() {
+                    println!("{}", name(id));
+                }
+            }
+        }
         Ok(())
     }
 }
 
+/// Show how one node depends on another
+#[derive(Args, Debug)]
+struct PathCommand {
+    #[command(flatten)]
+    input: InputArgs,
+
+    #[arg(value_name = "FROM")]
+    source: String,
+
+    #[arg(value_name = "TO")]
+    target: String,
+
+    /// Find every simple path instead of only the shortest one
+    #[arg(long)]
+    #[clap(default_value_t = false)]
+    all: bool,
+
+    /// Maximum number of paths reported with --all
+    #[arg(long, requires = "all")]
+    #[clap(default_value_t = 10)]
+    limit: usize,
+
+    /// Render the whole graph with the paths highlighted instead of printing them
+    #[arg(short, long)]
+    output: Option<String>,
+
+    #[command(flatten)]
+    render: RenderArgs,
+}
+
+impl PathCommand {
+    fn run(&self) -> anyhow::Result<()> {
+        let mut graph = self.input.build_graph()?;
+        let from = find_node(&graph, &self.source)?;
+        let to = find_node(&graph, &self.target)?;
+
+        let paths = if self.all {
+            algo::simple_paths(&graph, from, to, self.limit)
+        } else {
+            algo::shortest_path(&graph, from, to).into_iter().collect()
+        };
+        if paths.is_empty() {
+            return Err(anyhow::anyhow!(
+                "no path from \"{}\" to \"{}\"",
+                self.source,
+                self.target
+            ));
+        }
+
+        let Some(output) = &self.output else {
+            for path in paths.iter() {
+                let names = path
+                    .iter()
+                    .map(|&id| graph.node_name(id).unwrap())
+                    .join(" -> ");
+                println!("{}", names);
+            }
+            return Ok(());
+        };
+
+        let mut edges = HashSet::new();
+        for path in paths.iter() {
+            for &node in path.iter() {
+                highlight(graph.node_attributes_mut(node));
+            }
+            edges.extend(path.iter().copied().tuple_windows::<(_, _)>());
+        }
+        for id in 0..graph.edge_count() {
+            let edge = graph.edge_mut(id).unwrap();
+            if edges.contains(&(edge.from, edge.to)) {
+                highlight(&mut edge.attributes);
+            }
+        }
+        self.render.render(&graph, output)
+    }
+}
+
+/// Compare two inputs and render the changes
+#[derive(Args, Debug)]
+struct DiffCommand {
+    #[command(flatten)]
+    input: InputArgs,
+
+    old: String,
+
+    new: String,
+
+    #[arg(short, long)]
+    #[clap(default_value = "diff.svg")]
+    output: String,
+
+    #[command(flatten)]
+    render: RenderArgs,
+}
+
+impl DiffCommand {
+    fn run(&self) -> anyhow::Result<()> {
+        let old = self.input.build_graph_from_file(&self.old)?;
+        let new = self.input.build_graph_from_file(&self.new)?;
+        let diff::Diff {
+            mut graph,
+            nodes,
+            edges,
+        } = diff::diff(&old, &new);
+
+        let color = |change| match change {
+            diff::Change::Added => "green",
+            diff::Change::Removed => "red",
+            diff::Change::Unchanged => "gray",
+        };
+        for (id, &change) in nodes.iter().enumerate() {
+            let attributes = graph.node_attributes_mut(id);
+            attributes.insert("color".to_string(), color(change).to_string());
+            attributes.insert("fontcolor".to_string(), color(change).to_string());
+        }
+
+        let mut added = 0;
+        let mut removed = 0;
+        for (id, &change) in edges.iter().enumerate() {
+            let edge = graph.edge_mut(id).unwrap();
+            edge.attributes
+                .insert("color".to_string(), color(change).to_string());
+            edge.attributes
+                .insert("fontcolor".to_string(), color(change).to_string());
+
+            let sign = match change {
+                diff::Change::Added => {
+                    added += 1;
+                    '+'
+                }
+                diff::Change::Removed => {
+                    removed += 1;
+                    '-'
+                }
+                diff::Change::Unchanged => continue,
+            };
+            let edge = graph.edge(id).unwrap();
+            let label = edge
+                .label
+                .as_ref()
+                .map(|label| format!(": {}", label))
+                .unwrap_or_default();
+            println!(
+                "{} {} -> {}{}",
+                sign,
+                graph.node_name(edge.from).unwrap(),
+                graph.node_name(edge.to).unwrap(),
+                label
+            );
+        }
+        println!("{} edge(s) added, {} edge(s) removed", added, removed);
+
+        self.render.render(&graph, &self.output)
+    }
+}
+
+/// Scan a source tree and print its dependencies as an edge list
+#[derive(Args, Debug)]
+struct ScanCommand {
+    #[command(subcommand)]
+    language: ScanLanguage,
+
+    #[arg(long, global = true)]
+    #[clap(default_value = "->")]
+    edge_delimiter: String,
+
+    #[arg(long, global = true)]
+    #[clap(default_value = ":")]
+    label_delimiter: String,
+}
+
+#[derive(Subcommand, Debug)]
+enum ScanLanguage {
+    /// C and C++ #include directives
+    Cpp(ScanCppArgs),
+    /// Rust modules and their `use` of each other
+    Rust(ScanRustArgs),
+    /// Python modules and their imports
+    Python(ScanPythonArgs),
+    /// JavaScript and TypeScript files and their imports
+    Js(ScanJsArgs),
+}
+
+#[derive(Args, Debug)]
+struct ScanCppArgs {
+    dir: PathBuf,
+
+    /// Directory searched for includes (can be repeated)
+    #[arg(short = 'I', long = "include-path")]
+    include_paths: Vec<PathBuf>,
+
+    /// What to do with <...> includes that resolve to no file
+    #[arg(long, value_enum, default_value_t = SystemHeaders::default())]
+    system_headers: SystemHeaders,
+}
+
+#[derive(Args, Debug)]
+struct ScanRustArgs {
+    /// Directory with the Cargo.toml of the package
+    #[arg(value_name = "CRATE_ROOT")]
+    dir: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct ScanPythonArgs {
+    dir: PathBuf,
+
+    /// Leave out imports of modules outside the tree, like the standard library
+    #[arg(long)]
+    #[clap(default_value_t = false)]
+    drop_third_party: bool,
+}
+
+#[derive(Args, Debug)]
+struct ScanJsArgs {
+    dir: PathBuf,
+
+    /// Leave out imports of packages
+    #[arg(long)]
+    #[clap(default_value_t = false)]
+    drop_packages: bool,
+}
+
+impl ScanCommand {
+    fn run(&self) -> anyhow::Result<()> {
+        let graph = match &self.language {
+            ScanLanguage::Cpp(args) => {
+                let options = scan::cpp::Options {
+                    include_paths: args.include_paths.clone(),
+                    system_headers: (&args.system_headers).into(),
+                };
+                scan::cpp::scan(&args.dir, &options)?
+            }
+            ScanLanguage::Rust(args) => scan::rust::scan(&args.dir)?,
+            ScanLanguage::Python(args) => {
+                let options = scan::python::Options {
+                    drop_third_party: args.drop_third_party,
+                };
+                scan::python::scan(&args.dir, &options)?
+            }
+            ScanLanguage::Js(args) => {
+                let options = scan::js::Options {
+                    drop_packages: args.drop_packages,
+                };
+                scan::js::scan(&args.dir, &options)?
+            }
+        };
+        import::edges::write(
+            &graph,
+            &self.edge_delimiter,
+            &self.label_delimiter,
+            &mut io::stdout().lock(),
+        )
+    }
+}
+
+fn show_multiplicity(edge: &mut Edge, count: usize, multiplicity: &Multiplicity) {
+    if matches!(multiplicity, Multiplicity::Penwidth | Multiplicity::Both) {
+        let penwidth = (1.0 + (count as f64).log2()).min(8.0);
+        edge.attributes
+            .insert("penwidth".to_string(), format!("{:.1}", penwidth));
+    }
+    if matches!(multiplicity, Multiplicity::Label | Multiplicity::Both) {
+        edge.label = Some(match edge.label.take() {
+            Some(label) => format!("{} ×{}", label, count),
+            None => format!("×{}", count),
+        });
+    }
+}
+
+fn highlight(attributes: &mut Attributes) {
+    attributes.insert("color".to_string(), "red".to_string());
+    attributes.insert("penwidth".to_string(), "2".to_string());
+}
+
 #[derive(Args, Debug)]
 struct KuzuCommand {
     output: String,
@@ -275,32 +819,52 @@ fn main() -> Result<(), Box<dyn error::Error>> {
     let deptree = DepTreeCommands::parse();
     match deptree.commands {
         Commands::Graph(graph) => graph.run()?,
+        Commands::Check(check) => check.run()?,
+        Commands::Order(order) => order.run()?,
+        Commands::Path(path) => path.run()?,
+        Commands::Diff(diff) => diff.run()?,
+        Commands::Scan(scan) => scan.run()?,
         Commands::Kuzu(kuzu) => kuzu.run()?,
     }
     Ok(())
 }
 
-fn parse_line<'a>(
-    line: &'a str,
-    edge_delim: &str,
-    label_delim: &str,
-) -> Option<(&'a str, &'a str, Option<&'a str>)> {
-    // parse line
-    // a->b:foo
-
-    let mut split = line.split(label_delim);
-    let edge = split.next()?;
-    let label = split.next();
+fn find_node(graph: &Graph, name: &str) -> anyhow::Result<NodeId> {
+    graph
+        .node_id(name)
+        .with_context(|| format!("unknown node \"{}\"", name))
+}
 
-    let mut split = edge.split(edge_delim);
-    Some((split.next()?, split.next()?, label))
+fn read_input() -> io::Result<String> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    Ok(input)
 }
 
-fn read_input() -> io::Result<Vec<String>> {
-    let mut ret = Vec::new();
-    let stdin = io::stdin();
-    for line in stdin.lock().lines() {
-        ret.push(line?);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli() {
+        DepTreeCommands::command().debug_assert();
+    }
+
+    #[test]
+    fn test_check_limit() {
+        let graph = import::edges::parse("a->b\nb->a\nb->c\nc->a\n", "->", ":").unwrap();
+        for limit in ["1", "2", "100"] {
+            let cli = DepTreeCommands::parse_from(["deptree", "check", "--limit", limit]);
+            let Commands::Check(check) = cli.commands else {
+                panic!("not a check command");
+            };
+            assert!(
+                check.check(&graph).is_err(),
+                "passed with --limit {}",
+                limit
+            );
+        }
+        assert!(DepTreeCommands::try_parse_from(["deptree", "check", "--limit", "0"]).is_err());
     }
-    Ok(ret)
 }
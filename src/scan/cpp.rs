@@ -0,0 +1,193 @@
+//! C and C++ `#include` scanner.
+//!
+//! Every source file below the root becomes a node named by its path relative
+//! to the root, with an edge per `#include` directive.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::{fileutil, import, Graph};
+
+const EXTENSIONS: [&str; 13] = [
+    "c", "cc", "cpp", "cxx", "c++", "h", "hh", "hpp", "hxx", "h++", "ipp", "inl", "tpp",
+];
+
+/// What to do with `<...>` includes that resolve to no file.
+#[derive(Debug, Clone, Default)]
+pub enum SystemHeaders {
+    /// One node per header, e.g. `<vector>`
+    #[default]
+    Keep,
+    /// A single `<system>` node
+    Group,
+    /// No node at all
+    Drop,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Directories searched for includes, after the including file's
+    /// directory for `"..."` includes
+    pub include_paths: Vec<PathBuf>,
+    pub system_headers: SystemHeaders,
+}
+
+/// Scans the C and C++ files below `root`.
+///
+/// Unresolved `"..."` includes are kept as nodes named as written, quotes
+/// included.
+pub fn scan(root: &Path, options: &Options) -> anyhow::Result<Graph> {
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("failed to read {}", root.display()))?;
+    let include_paths = options
+        .include_paths
+        .iter()
+        .map(|path| {
+            path.canonicalize()
+                .with_context(|| format!("failed to read include path {}", path.display()))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut graph = Graph::new();
+    for file in fileutil::source_files(&root, &EXTENSIONS)? {
+        let from = super::relative_name(&root, &file);
+        graph.insert_node(&from);
+
+        let source = fs::read(&file).with_context(|| format!("failed to read {}", from))?;
+        let dir = file.parent().unwrap_or(&root);
+        for (quoted, header) in includes(&String::from_utf8_lossy(&source)) {
+            let search = quoted
+                .then_some(dir)
+                .into_iter()
+                .chain(include_paths.iter().map(PathBuf::as_path));
+            let resolved = search
+                .map(|dir| dir.join(header))
+                .find(|path| path.is_file())
+                .and_then(|path| path.canonicalize().ok());
+            let to = match (resolved, quoted, &options.system_headers) {
+                (Some(path), _, _) => super::relative_name(&root, &path),
+                (None, true, _) => format!("\"{}\"", header),
+                (None, false, SystemHeaders::Keep) => format!("<{}>", header),
+                (None, false, SystemHeaders::Group) => "<system>".to_string(),
+                (None, false, SystemHeaders::Drop) => continue,
+            };
+            import::add_edge(&mut graph, &from, &to, None);
+        }
+    }
+    Ok(graph)
+}
+
+// (quoted, header) of every `#include "header"` and `#include <header>`,
+// skipping block comments
+fn includes(source: &str) -> Vec<(bool, &str)> {
+    let mut includes = Vec::new();
+    let mut in_comment = false;
+    for line in source.lines() {
+        let mut line = line;
+        if in_comment {
+            match line.find("*/") {
+                Some(end) => {
+                    line = &line[end + 2..];
+                    in_comment = false;
+                }
+                None => continue,
+            }
+        }
+        if let Some(start) = line.rfind("/*") {
+            in_comment = !line[start..].contains("*/");
+        }
+
+        let Some(directive) = line.trim_start().strip_prefix('#') else {
+            continue;
+        };
+        let Some(rest) = directive.trim_start().strip_prefix("include") else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        let (quoted, close) = match rest.chars().next() {
+            Some('"') => (true, '"'),
+            Some('<') => (false, '>'),
+            _ => continue,
+        };
+        if let Some(end) = rest[1..].find(close) {
+            includes.push((quoted, &rest[1..end + 1]));
+        }
+    }
+    includes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_includes() {
+        let source = "#include \"a.h\"\n  #  include <vector> // std\n/*\n#include \"b.h\"\n*/\n#define X\n#include MACRO\n";
+        assert_eq!(includes(source), vec![(true, "a.h"), (false, "vector")]);
+    }
+
+    #[test]
+    fn test_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("include/lib")).unwrap();
+        fs::write(
+            root.join("src/main.cpp"),
+            "#include \"util.h\"\n#include <lib/lib.h>\n#include <vector>\n#include \"missing.h\"\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/util.h"), "#include <string>\n").unwrap();
+        fs::write(
+            root.join("include/lib/lib.h"),
+            "#include \"../../src/util.h\"\n",
+        )
+        .unwrap();
+
+        let mut options = Options {
+            include_paths: vec![root.join("include")],
+            system_headers: SystemHeaders::Keep,
+        };
+        let graph = scan(root, &options).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("include/lib/lib.h", "src/util.h"),
+                ("src/main.cpp", "src/util.h"),
+                ("src/main.cpp", "include/lib/lib.h"),
+                ("src/main.cpp", "<vector>"),
+                ("src/main.cpp", "\"missing.h\""),
+                ("src/util.h", "<string>"),
+            ]
+        );
+
+        options.system_headers = SystemHeaders::Group;
+        let graph = scan(root, &options).unwrap();
+        assert_eq!(graph.node_id("<vector>"), None);
+        assert_eq!(graph.in_degree(graph.node_id("<system>").unwrap()), 2);
+
+        options.system_headers = SystemHeaders::Drop;
+        let graph = scan(root, &options).unwrap();
+        assert_eq!(graph.node_id("<system>"), None);
+        assert_eq!(graph.edge_count(), 4);
+
+        options.include_paths.push(root.join("inclde"));
+        let err = scan(root, &options).err().unwrap();
+        assert!(err.to_string().contains("inclde"), "{}", err);
+    }
+}
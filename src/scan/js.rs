@@ -0,0 +1,499 @@
+//! JavaScript and TypeScript import scanner.
+//!
+//! Every source file becomes a node named by its path relative to the root,
+//! with an edge per `import`, `export ... from`, `require()` and dynamic
+//! `import()`. Edges of dynamic imports are labelled `dynamic`. Specifiers
+//! are resolved relative to the importing file or through the `baseUrl` and
+//! `paths` of the root's `tsconfig.json` (`extends` is not followed); others
+//! point to a node named after their package.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::{fileutil, import, Graph};
+
+const EXTENSIONS: [&str; 8] = ["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Leave out imports of packages
+    pub drop_packages: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Punct(char),
+}
+
+#[derive(Debug, PartialEq)]
+struct Import {
+    specifier: String,
+    dynamic: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct TsConfig {
+    #[serde(default, rename = "compilerOptions")]
+    compiler_options: CompilerOptions,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CompilerOptions {
+    base_url: Option<PathBuf>,
+    #[serde(default)]
+    paths: BTreeMap<String, Vec<String>>,
+}
+
+struct Resolver {
+    // directory `paths` are relative to
+    base: PathBuf,
+    base_url: Option<PathBuf>,
+    paths: BTreeMap<String, Vec<String>>,
+}
+
+/// Scans the JavaScript and TypeScript files below `root`.
+pub fn scan(root: &Path, options: &Options) -> anyhow::Result<Graph> {
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("failed to read {}", root.display()))?;
+    let resolver = Resolver::new(&root)?;
+
+    let mut graph = Graph::new();
+    let files = fileutil::source_files(&root, &EXTENSIONS)?;
+    for file in files.iter() {
+        graph.insert_node(&super::relative_name(&root, file));
+    }
+    for file in files.iter() {
+        let from = super::relative_name(&root, file);
+        let source = fs::read(file).with_context(|| format!("failed to read {}", from))?;
+        let dir = file.parent().unwrap_or(&root);
+
+        for import in imports(&String::from_utf8_lossy(&source)) {
+            let to = match resolver.resolve(dir, &import.specifier) {
+                Some(path) => super::relative_name(&root, &path),
+                None if is_relative(&import.specifier) => {
+                    log::debug!("{}: unresolved import \"{}\"", from, import.specifier);
+                    continue;
+                }
+                None if options.drop_packages => continue,
+                None => package_name(&import.specifier).to_string(),
+            };
+            let label = import.dynamic.then(|| "dynamic".to_string());
+            import::add_edge(&mut graph, &from, &to, label);
+        }
+    }
+    Ok(graph)
+}
+
+fn is_relative(specifier: &str) -> bool {
+    specifier.starts_with("./") || specifier.starts_with("../") || specifier.starts_with('/')
+}
+
+// "@scope/pkg/sub" => "@scope/pkg", "pkg/sub" => "pkg", "node:fs" => "node:fs"
+fn package_name(specifier: &str) -> &str {
+    let segments = match specifier.starts_with('@') {
+        true => 2,
+        false => 1,
+    };
+    match specifier.match_indices('/').nth(segments - 1) {
+        Some((idx, _)) => &specifier[..idx],
+        None => specifier,
+    }
+}
+
+impl Resolver {
+    fn new(root: &Path) -> anyhow::Result<Self> {
+        let config = ["tsconfig.json", "jsconfig.json"]
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.is_file());
+        let config = match config {
+            Some(path) => {
+                let input = fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                serde_json::from_str::<TsConfig>(&strip_jsonc(&input))
+                    .with_context(|| format!("invalid {}", path.display()))?
+            }
+            None => TsConfig::default(),
+        };
+
+        let base_url = config.compiler_options.base_url.map(|url| root.join(url));
+        Ok(Resolver {
+            base: base_url.clone().unwrap_or_else(|| root.to_path_buf()),
+            base_url,
+            paths: config.compiler_options.paths,
+        })
+    }
+
+    fn resolve(&self, dir: &Path, specifier: &str) -> Option<PathBuf> {
+        if is_relative(specifier) {
+            return resolve_file(&dir.join(specifier));
+        }
+
+        // the matching pattern with the longest prefix before its '*'
+        let mut best: Option<(usize, Vec<String>)> = None;
+        for (pattern, targets) in self.paths.iter() {
+            let (prefix, captured) = match pattern.split_once('*') {
+                Some((prefix, suffix)) => {
+                    let Some(captured) = specifier
+                        .strip_prefix(prefix)
+                        .and_then(|rest| rest.strip_suffix(suffix))
+                    else {
+                        continue;
+                    };
+                    (prefix, captured)
+                }
+                None if pattern == specifier => (pattern.as_str(), ""),
+                None => continue,
+            };
+            if best.as_ref().is_none_or(|(len, _)| prefix.len() > *len) {
+                let targets = targets.iter().map(|t| t.replacen('*', captured, 1));
+                best = Some((prefix.len(), targets.collect()));
+            }
+        }
+        if let Some((_, targets)) = best {
+            return targets
+                .iter()
+                .find_map(|target| resolve_file(&self.base.join(target)));
+        }
+
+        let base_url = self.base_url.as_ref()?;
+        resolve_file(&base_url.join(specifier))
+    }
+}
+
+// `path` as written, with an extension, or as a directory with an index
+// file; `.js` also finds the `.ts` file it is compiled from
+fn resolve_file(path: &Path) -> Option<PathBuf> {
+    let mut candidates = vec![path.to_path_buf()];
+    let as_str = path.as_os_str().to_string_lossy();
+    for (js, ts) in [
+        (".js", ".ts"),
+        (".js", ".tsx"),
+        (".jsx", ".tsx"),
+        (".mjs", ".mts"),
+        (".cjs", ".cts"),
+    ] {
+        if let Some(stem) = as_str.strip_suffix(js) {
+            candidates.push(PathBuf::from(format!("{}{}", stem, ts)));
+        }
+    }
+    for ext in EXTENSIONS.iter() {
+        candidates.push(PathBuf::from(format!("{}.{}", as_str, ext)));
+    }
+    for ext in EXTENSIONS.iter() {
+        candidates.push(path.join(format!("index.{}", ext)));
+    }
+    candidates
+        .into_iter()
+        .find(|path| path.is_file())
+        .and_then(|path| path.canonicalize().ok())
+}
+
+// tsconfig.json allows comments and trailing commas
+fn strip_jsonc(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                output.push(c);
+                while let Some(c) = chars.next() {
+                    output.push(c);
+                    match c {
+                        '\\' => output.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            '}' | ']' => {
+                let trimmed = output.trim_end().len();
+                if output[..trimmed].ends_with(',') {
+                    output.truncate(trimmed - 1);
+                }
+                output.push(c);
+            }
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+fn imports(source: &str) -> Vec<Import> {
+    let tokens = tokenize(source);
+    let ident =
+        |idx: usize, name: &str| matches!(tokens.get(idx), Some(Token::Ident(s)) if s == name);
+    let punct = |idx: usize, c: char| tokens.get(idx) == Some(&Token::Punct(c));
+    let string = |idx: usize| match tokens.get(idx) {
+        Some(Token::Str(s)) => Some(s.clone()),
+        _ => None,
+    };
+
+    let mut imports = Vec::new();
+    for idx in 0..tokens.len() {
+        // foo.import / foo.require are not the keywords
+        if idx > 0 && punct(idx - 1, '.') {
+            continue;
+        }
+        let (specifier, dynamic) = if ident(idx, "import") && punct(idx + 1, '(') {
+            (string(idx + 2), true)
+        } else if ident(idx, "require") && punct(idx + 1, '(') && punct(idx + 3, ')') {
+            (string(idx + 2), false)
+        } else if ident(idx, "import") {
+            match string(idx + 1) {
+                // import "./side-effect"
+                Some(specifier) => (Some(specifier), false),
+                None => (from_clause(&tokens, idx + 1, false), false),
+            }
+        } else if ident(idx, "export") {
+            (from_clause(&tokens, idx + 1, true), false)
+        } else {
+            continue;
+        };
+        if let Some(specifier) = specifier.filter(|s| !s.is_empty()) {
+            imports.push(Import { specifier, dynamic });
+        }
+    }
+    imports
+}
+
+// the specifier of `[type] default, { a as b } from "specifier"` or
+// `* as ns from "specifier"` starting at `idx`; exports have no default
+fn from_clause(tokens: &[Token], mut idx: usize, export: bool) -> Option<String> {
+    let ident =
+        |idx: usize, name: &str| matches!(tokens.get(idx), Some(Token::Ident(s)) if s == name);
+    if ident(idx, "type")
+        && !ident(idx + 1, "from")
+        && tokens.get(idx + 1) != Some(&Token::Punct(','))
+    {
+        idx += 1;
+    }
+    if !export {
+        if let Some(Token::Ident(name)) = tokens.get(idx) {
+            if name != "from" || ident(idx + 1, "from") {
+                idx += 1;
+            }
+            if tokens.get(idx) == Some(&Token::Punct(',')) {
+                idx += 1;
+            }
+        }
+    }
+    match tokens.get(idx)? {
+        Token::Punct('{') => {
+            idx += tokens[idx..].iter().position(|t| *t == Token::Punct('}'))? + 1;
+        }
+        Token::Punct('*') if ident(idx + 1, "as") => idx += 3,
+        Token::Punct('*') => idx += 1,
+        Token::Ident(s) if s == "from" && !export => {}
+        _ => return None,
+    }
+    match (tokens.get(idx), tokens.get(idx + 1)) {
+        (Some(Token::Ident(s)), Some(Token::Str(specifier))) if s == "from" => {
+            Some(specifier.clone())
+        }
+        _ => None,
+    }
+}
+
+// identifiers, string literals and punctuation, without comments, template
+// literals, regular expressions and numbers
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            // a regular expression unless it follows an operand
+            '/' if !matches!(
+                tokens.last(),
+                Some(Token::Ident(_)) | Some(Token::Str(_)) | Some(Token::Punct(')' | ']' | '}'))
+            ) =>
+            {
+                let mut class = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '[' => class = true,
+                        ']' => class = false,
+                        '/' if !class => break,
+                        '\n' => break,
+                        _ => {}
+                    }
+                }
+                // stands for the literal, an operand
+                tokens.push(Token::Str(String::new()));
+            }
+            '\'' | '"' | '`' => {
+                let mut s = String::new();
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' => s.extend(chars.next()),
+                        next if next == c => break,
+                        '\n' if c != '`' => break,
+                        next => s.push(next),
+                    }
+                }
+                // template literals are never specifiers
+                tokens.push(Token::Str(match c {
+                    '`' => String::new(),
+                    _ => s,
+                }));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let mut s = c.to_string();
+                while let Some(c) = chars.next_if(|&c| c.is_alphanumeric() || c == '_' || c == '$')
+                {
+                    s.push(c);
+                }
+                tokens.push(Token::Ident(s));
+            }
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                    edge.label.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_imports() {
+        let source = r#"
+import React, { useState } from "react";
+import * as path from 'node:path'
+import type { Props } from "./types"
+import "./styles.css";
+export * from './a'
+export { b as c } from "./b";
+export type { D } from './d';
+export const x = 1 / 2; const re = /from "nope"/;
+// import "./commented"
+/* require("./commented") */
+const e = require('./e');
+const f = await import("./f");
+const g = import.meta.url + obj.require("./no");
+const h = `import "./template"`;
+import i = require("./i");
+export { j }
+import k from "./k"
+import type, { l } from "./l";
+import type from "./m";
+"#;
+        let specifiers = imports(source)
+            .into_iter()
+            .map(|i| (i.specifier, i.dynamic))
+            .collect::<Vec<_>>();
+        let expected = [
+            ("react", false),
+            ("node:path", false),
+            ("./types", false),
+            ("./styles.css", false),
+            ("./a", false),
+            ("./b", false),
+            ("./d", false),
+            ("./e", false),
+            ("./f", true),
+            ("./i", false),
+            ("./k", false),
+            ("./l", false),
+            ("./m", false),
+        ];
+        assert_eq!(
+            specifiers,
+            expected
+                .iter()
+                .map(|(s, d)| (s.to_string(), *d))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/components")).unwrap();
+        let files = [
+            (
+                "tsconfig.json",
+                "{\n  // comment\n  \"compilerOptions\": {\n    \"baseUrl\": \".\",\n    \"paths\": { \"@/*\": [\"src/*\"], },\n  },\n}\n",
+            ),
+            (
+                "src/index.ts",
+                "import { Button } from '@/components';\nimport lodash from 'lodash/fp';\nconst page = () => import('./page.js');\n",
+            ),
+            ("src/page.tsx", "import { Button } from './components/Button';\n"),
+            ("src/components/index.ts", "export * from './Button';\n"),
+            ("src/components/Button.tsx", "import { theme } from 'src/theme';\nimport '@scope/ui/button.css';\n"),
+            ("src/theme.js", "module.exports = require('./components');\n"),
+        ];
+        for (file, content) in files {
+            fs::write(root.join(file), content).unwrap();
+        }
+
+        let graph = scan(root, &Options::default()).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("src/components/Button.tsx", "src/theme.js", None),
+                ("src/components/Button.tsx", "@scope/ui", None),
+                ("src/components/index.ts", "src/components/Button.tsx", None),
+                ("src/index.ts", "src/components/index.ts", None),
+                ("src/index.ts", "lodash", None),
+                ("src/index.ts", "src/page.tsx", Some("dynamic")),
+                ("src/page.tsx", "src/components/Button.tsx", None),
+                ("src/theme.js", "src/components/index.ts", None),
+            ]
+        );
+
+        let options = Options {
+            drop_packages: true,
+        };
+        assert_eq!(scan(root, &options).unwrap().edge_count(), 6);
+    }
+}
@@ -0,0 +1,19 @@
+//! Scanners that build a [`Graph`](crate::Graph) from a source tree.
+
+use std::path::Path;
+
+pub mod cpp;
+pub mod js;
+pub mod python;
+pub mod rust;
+
+// `path` relative to `root` with '/' separators, or as given if it is
+// outside of `root`
+fn relative_name(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
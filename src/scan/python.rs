@@ -0,0 +1,344 @@
+//! Python import scanner.
+//!
+//! Every `.py` file becomes a module node named by its dotted path, with
+//! `__init__.py` standing for its package. If the scanned directory is itself
+//! a package, names start at the first parent directory that is not one.
+//! Imports of modules outside the tree (standard library and third-party)
+//! point to a node named after their top-level module.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::{fileutil, import, Graph};
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Leave out imports of modules outside the tree
+    pub drop_third_party: bool,
+}
+
+// `import module` (no names) or `from .module import names`
+#[derive(Debug, PartialEq)]
+struct Import {
+    level: usize,
+    module: String,
+    names: Vec<String>,
+}
+
+/// Scans the Python files below `root`.
+pub fn scan(root: &Path, options: &Options) -> anyhow::Result<Graph> {
+    let mut base = root
+        .canonicalize()
+        .with_context(|| format!("failed to read {}", root.display()))?;
+    while base.join("__init__.py").is_file() {
+        match base.parent() {
+            Some(parent) => base = parent.to_path_buf(),
+            None => break,
+        }
+    }
+
+    let mut files = Vec::new();
+    for file in fileutil::source_files(&root.canonicalize()?, &["py"])? {
+        let name = super::relative_name(&base, &file.with_extension(""));
+        let mut path = name.split('/').map(str::to_string).collect::<Vec<_>>();
+        let package = match path.last().map(String::as_str) {
+            Some("__init__") => {
+                path.pop();
+                path.clone()
+            }
+            _ => path[..path.len() - 1].to_vec(),
+        };
+        files.push((file, path, package));
+    }
+    let modules = files
+        .iter()
+        .map(|(_, path, _)| path.join("."))
+        .collect::<HashSet<_>>();
+
+    let mut graph = Graph::new();
+    for (_, path, _) in files.iter() {
+        graph.insert_node(&path.join("."));
+    }
+    for (file, path, package) in files.iter() {
+        let from = path.join(".");
+        let source = fs::read(file).with_context(|| format!("failed to read {}", from))?;
+
+        for import in imports(&String::from_utf8_lossy(&source)) {
+            let mut base = match import.level {
+                0 => Vec::new(),
+                level => match package.len().checked_sub(level - 1) {
+                    Some(len) => package[..len].to_vec(),
+                    None => {
+                        log::debug!("{}: relative import beyond the top-level package", from);
+                        continue;
+                    }
+                },
+            };
+            base.extend(
+                import
+                    .module
+                    .split('.')
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string),
+            );
+
+            // `from package import module` imports the submodule
+            let submodules = import
+                .names
+                .iter()
+                .map(|name| format!("{}.{}", base.join("."), name))
+                .filter(|name| modules.contains(name))
+                .collect::<Vec<_>>();
+            let mut targets = submodules.clone();
+            if submodules.len() < import.names.len() || import.names.is_empty() {
+                let target = match (1..=base.len())
+                    .rev()
+                    .map(|len| base[..len].join("."))
+                    .find(|name| modules.contains(name))
+                {
+                    Some(name) => name,
+                    None if import.level > 0 => {
+                        log::debug!("{}: unresolved relative import {:?}", from, import);
+                        continue;
+                    }
+                    None if options.drop_third_party => continue,
+                    None => match base.first() {
+                        Some(top) => top.clone(),
+                        None => continue,
+                    },
+                };
+                targets.push(target);
+            }
+
+            for to in targets {
+                if to != from {
+                    import::add_edge(&mut graph, &from, &to, None);
+                }
+            }
+        }
+    }
+    Ok(graph)
+}
+
+fn imports(source: &str) -> Vec<Import> {
+    let mut imports = Vec::new();
+    for line in logical_lines(source) {
+        for statement in line.split(';') {
+            imports.extend(parse_statement(statement.trim()));
+        }
+    }
+    imports
+}
+
+fn parse_statement(statement: &str) -> Vec<Import> {
+    if let Some(rest) = statement.strip_prefix("import") {
+        if !rest.starts_with(char::is_whitespace) {
+            return Vec::new();
+        }
+        // import a.b as c, d
+        return rest
+            .split(',')
+            .filter_map(|part| part.split_whitespace().next())
+            .map(|module| Import {
+                level: 0,
+                module: module.to_string(),
+                names: Vec::new(),
+            })
+            .collect();
+    }
+
+    // from ..a.b import (c as d, e)
+    let Some(rest) = statement.strip_prefix("from") else {
+        return Vec::new();
+    };
+    if !rest.starts_with(|c: char| c.is_whitespace() || c == '.') {
+        return Vec::new();
+    }
+    let keyword = rest.match_indices("import").find(|&(idx, _)| {
+        rest[..idx].ends_with(|c: char| c.is_whitespace() || c == '.')
+            && rest[idx + "import".len()..].starts_with(|c: char| c.is_whitespace() || c == '(')
+    });
+    let Some((idx, _)) = keyword else {
+        return Vec::new();
+    };
+    let module = rest[..idx].trim();
+    let level = module.chars().take_while(|&c| c == '.').count();
+    let names = rest[idx + "import".len()..]
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .filter_map(|part| part.split_whitespace().next())
+        .filter(|name| *name != "*")
+        .map(str::to_string)
+        .collect();
+    vec![Import {
+        level,
+        module: module[level..].trim().to_string(),
+        names,
+    }]
+}
+
+// joins bracketed and backslash continued lines, dropping comments and the
+// contents of string literals
+fn logical_lines(source: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut depth = 0usize;
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => while chars.next_if(|&c| c != '\n').is_some() {},
+            '\\' if chars.peek() == Some(&'\n') => {
+                chars.next();
+                line.push(' ');
+            }
+            '\'' | '"' => {
+                let triple = chars.next_if_eq(&c).is_some();
+                if triple && chars.next_if_eq(&c).is_none() {
+                    // an empty string
+                    line.push_str("\"\"");
+                    continue;
+                }
+                let mut closing = 0;
+                while let Some(s) = chars.next() {
+                    match s {
+                        '\\' => {
+                            chars.next();
+                            closing = 0;
+                        }
+                        '\n' if !triple => break,
+                        s if s == c => {
+                            closing += 1;
+                            if !triple || closing == 3 {
+                                break;
+                            }
+                        }
+                        _ => closing = 0,
+                    }
+                }
+                line.push_str("\"\"");
+            }
+            '(' | '[' | '{' => {
+                depth += 1;
+                line.push(c);
+            }
+            ')' | ']' | '}' => {
+                depth = depth.saturating_sub(1);
+                line.push(c);
+            }
+            '\n' if depth > 0 => line.push(' '),
+            '\n' => lines.push(std::mem::take(&mut line)),
+            c => line.push(c),
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn import(level: usize, module: &str, names: &[&str]) -> Import {
+        Import {
+            level,
+            module: module.to_string(),
+            names: names.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_imports() {
+        let source = r#""""docstring
+import fake
+"""
+import os.path as p, sys  # import comment
+from . import (a,
+    b as c)
+from ..pkg.mod import *; from .important import y \
+    , z
+def f():
+    s = 'import quoted'
+    import json
+important = 1
+"#;
+        assert_eq!(
+            imports(source),
+            vec![
+                import(0, "os.path", &[]),
+                import(0, "sys", &[]),
+                import(1, "", &["a", "b"]),
+                import(2, "pkg.mod", &[]),
+                import(1, "important", &["y", "z"]),
+                import(0, "json", &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("app");
+        fs::create_dir_all(root.join("models")).unwrap();
+        let files = [
+            ("__init__.py", "from .main import run\n"),
+            (
+                "main.py",
+                "import requests\nfrom app import models\nfrom app.models import user\n",
+            ),
+            (
+                "models/__init__.py",
+                "from . import user, base\nfrom ..main import run\n",
+            ),
+            (
+                "models/user.py",
+                "from .base import Base\nfrom .. import util\nimport os\n",
+            ),
+            ("models/base.py", "import app.models\n"),
+        ];
+        for (file, content) in files {
+            fs::write(root.join(file), content).unwrap();
+        }
+
+        let graph = scan(&root, &Options::default()).unwrap();
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("app", "app.main"),
+                ("app.main", "requests"),
+                ("app.main", "app.models"),
+                ("app.main", "app.models.user"),
+                ("app.models", "app.models.user"),
+                ("app.models", "app.models.base"),
+                ("app.models", "app.main"),
+                ("app.models.base", "app.models"),
+                ("app.models.user", "app.models.base"),
+                ("app.models.user", "app"),
+                ("app.models.user", "os"),
+            ]
+        );
+
+        let options = Options {
+            drop_third_party: true,
+        };
+        let graph = scan(&root, &options).unwrap();
+        assert_eq!(graph.node_id("os"), None);
+        assert_eq!(graph.edge_count(), 9);
+    }
+}
@@ -0,0 +1,395 @@
+//! Rust module scanner.
+//!
+//! Follows the `mod` declarations of `src/lib.rs` and `src/main.rs` and adds
+//! an edge for every `use` of another module of the package. Library modules
+//! are named after the crate, e.g. `deptree.import.cargo`, and binary
+//! modules after `main`; `use deptree::...` in the binary refers to the
+//! library. A module gets one edge per module it uses, however many `use`s
+//! name it. `#[cfg(test)]` modules are skipped.
+//!
+//! Names are joined with `.` rather than `::`, so that they can be written
+//! as an edge list with the default delimiters.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use syn::visit::Visit;
+
+use crate::{import, Graph};
+
+struct Module {
+    root: String,
+    path: Vec<String>,
+    uses: Vec<Vec<String>>,
+}
+
+impl Module {
+    fn name(&self) -> String {
+        module_name(&self.root, &self.path)
+    }
+}
+
+fn module_name(root: &str, path: &[String]) -> String {
+    std::iter::once(root)
+        .chain(path.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Scans the package whose `Cargo.toml` is in `root`.
+pub fn scan(root: &Path) -> anyhow::Result<Graph> {
+    let crate_name = crate_name(root)?;
+    let lib = root.join("src/lib.rs");
+    let main = root.join("src/main.rs");
+
+    let mut modules = Vec::new();
+    let has_lib = lib.is_file();
+    if has_lib {
+        collect_file(&lib, true, &crate_name, Vec::new(), &mut modules)?;
+    }
+    if main.is_file() {
+        collect_file(&main, true, "main", Vec::new(), &mut modules)?;
+    }
+    if modules.is_empty() {
+        return Err(anyhow::anyhow!(
+            "no src/lib.rs or src/main.rs in {}",
+            root.display()
+        ));
+    }
+
+    let mut graph = Graph::new();
+    let names = modules.iter().map(Module::name).collect::<HashSet<_>>();
+    for module in modules.iter() {
+        graph.insert_node(&module.name());
+    }
+    let lib_name = has_lib.then_some(crate_name.as_str());
+    for module in modules.iter() {
+        let from = module.name();
+        let mut targets = HashSet::new();
+        for path in module.uses.iter() {
+            let Some(to) = resolve(module, path, lib_name, &names) else {
+                continue;
+            };
+            if to != from && targets.insert(to.clone()) {
+                import::add_edge(&mut graph, &from, &to, None);
+            }
+        }
+    }
+    Ok(graph)
+}
+
+// the library name from Cargo.toml, with '-' replaced by '_'
+fn crate_name(root: &Path) -> anyhow::Result<String> {
+    let manifest = root.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest)
+        .with_context(|| format!("failed to read {}", manifest.display()))?;
+    let manifest: toml::Table = toml::from_str(&manifest).context("invalid Cargo.toml")?;
+    let name = ["lib", "package"]
+        .iter()
+        .find_map(|table| manifest.get(*table)?.get("name")?.as_str())
+        .context("Cargo.toml has no package name")?;
+    Ok(name.replace('-', "_"))
+}
+
+// `mod_rs` files (lib.rs, main.rs and mod.rs) keep their submodules next to
+// them, other files in a directory named after them
+fn collect_file(
+    file: &Path,
+    mod_rs: bool,
+    root: &str,
+    path: Vec<String>,
+    modules: &mut Vec<Module>,
+) -> anyhow::Result<()> {
+    let source =
+        fs::read_to_string(file).with_context(|| format!("failed to read {}", file.display()))?;
+    let ast =
+        syn::parse_file(&source).with_context(|| format!("failed to parse {}", file.display()))?;
+    let file_dir = file.parent().unwrap_or(Path::new("."));
+    let child_dir = match mod_rs {
+        true => file_dir.to_path_buf(),
+        false => file_dir.join(file.file_stem().unwrap_or_default()),
+    };
+    collect_items(&ast.items, file_dir, &child_dir, root, path, modules)
+}
+
+fn collect_items(
+    items: &[syn::Item],
+    file_dir: &Path,
+    child_dir: &Path,
+    root: &str,
+    path: Vec<String>,
+    modules: &mut Vec<Module>,
+) -> anyhow::Result<()> {
+    let mut uses = UseCollector::default();
+    let mut children = Vec::new();
+    for item in items {
+        match item {
+            syn::Item::Mod(module) if !is_cfg_test(&module.attrs) => children.push(module),
+            syn::Item::Mod(_) => {}
+            item => uses.visit_item(item),
+        }
+    }
+    modules.push(Module {
+        root: root.to_string(),
+        path: path.clone(),
+        uses: uses.paths,
+    });
+
+    for module in children {
+        let ident = module.ident.to_string();
+        let mut child_path = path.clone();
+        child_path.push(ident.clone());
+
+        if let Some((_, items)) = &module.content {
+            let dir = child_dir.join(&ident);
+            collect_items(items, file_dir, &dir, root, child_path, modules)?;
+            continue;
+        }
+        let candidates = match path_attribute(&module.attrs) {
+            Some(file) => vec![(file_dir.join(file), true)],
+            None => vec![
+                (child_dir.join(format!("{}.rs", ident)), false),
+                (child_dir.join(&ident).join("mod.rs"), true),
+            ],
+        };
+        match candidates.into_iter().find(|(file, _)| file.is_file()) {
+            Some((file, mod_rs)) => collect_file(&file, mod_rs, root, child_path, modules)?,
+            None => {
+                log::warn!("no file for module {}", module_name(root, &child_path));
+                modules.push(Module {
+                    root: root.to_string(),
+                    path: child_path,
+                    uses: Vec::new(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_cfg_test(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("cfg")
+            && attr
+                .parse_args::<syn::Ident>()
+                .is_ok_and(|ident| ident == "test")
+    })
+}
+
+// #[path = "file.rs"]
+fn path_attribute(attrs: &[syn::Attribute]) -> Option<PathBuf> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        syn::Meta::NameValue(meta) if meta.path.is_ident("path") => match &meta.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(PathBuf::from(s.value())),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+// the flattened paths of all `use` items, outside of nested modules
+#[derive(Default)]
+struct UseCollector {
+    paths: Vec<Vec<String>>,
+}
+
+impl<'ast> Visit<'ast> for UseCollector {
+    fn visit_item_use(&mut self, item: &'ast syn::ItemUse) {
+        let mut paths = Vec::new();
+        flatten(&item.tree, &mut Vec::new(), &mut paths);
+        if item.leading_colon.is_some() {
+            // `::name` is always another crate, maybe this package's library
+            for path in paths.iter_mut() {
+                path.insert(0, "::".to_string());
+            }
+        }
+        self.paths.extend(paths);
+    }
+
+    fn visit_item_mod(&mut self, _: &'ast syn::ItemMod) {}
+}
+
+fn flatten(tree: &syn::UseTree, prefix: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+    match tree {
+        syn::UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            flatten(&path.tree, prefix, paths);
+            prefix.pop();
+        }
+        syn::UseTree::Name(name) if name.ident == "self" => paths.push(prefix.clone()),
+        syn::UseTree::Name(name) => paths.push(with(prefix, &name.ident)),
+        syn::UseTree::Rename(rename) if rename.ident == "self" => paths.push(prefix.clone()),
+        syn::UseTree::Rename(rename) => paths.push(with(prefix, &rename.ident)),
+        syn::UseTree::Glob(_) => paths.push(prefix.clone()),
+        syn::UseTree::Group(group) => {
+            for tree in group.items.iter() {
+                flatten(tree, prefix, paths);
+            }
+        }
+    }
+}
+
+fn with(prefix: &[String], ident: &syn::Ident) -> Vec<String> {
+    let mut path = prefix.to_vec();
+    path.push(ident.to_string());
+    path
+}
+
+// the deepest module of the package named by `path`, used from `module`
+fn resolve(
+    module: &Module,
+    path: &[String],
+    lib_name: Option<&str>,
+    names: &HashSet<String>,
+) -> Option<String> {
+    let is_child = |name: &str| {
+        let mut child = module.path.clone();
+        child.push(name.to_string());
+        names.contains(&module_name(&module.root, &child))
+    };
+    let (first, rest) = path.split_first()?;
+    let (root, mut base, rest) = match first.as_str() {
+        "::" => match rest.split_first() {
+            Some((name, rest)) if Some(name.as_str()) == lib_name => (name.as_str(), vec![], rest),
+            _ => return None,
+        },
+        "crate" => (module.root.as_str(), vec![], rest),
+        "self" => (module.root.as_str(), module.path.clone(), rest),
+        "super" => {
+            let supers = path.iter().take_while(|s| *s == "super").count();
+            let depth = module.path.len().checked_sub(supers)?;
+            (
+                module.root.as_str(),
+                module.path[..depth].to_vec(),
+                &path[supers..],
+            )
+        }
+        name if Some(name) == lib_name => (name, vec![], rest),
+        // a child module, e.g. `use cli::Args` next to `mod cli;`
+        name if is_child(name) => (module.root.as_str(), module.path.clone(), path),
+        _ => return None,
+    };
+    for segment in rest {
+        base.push(segment.clone());
+        if !names.contains(&module_name(root, &base)) {
+            base.pop();
+            break;
+        }
+    }
+    Some(module_name(root, &base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node_name(edge.from).unwrap(),
+                    graph.node_name(edge.to).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    // a library and a binary, with every kind of `use` the scanner follows
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/import")).unwrap();
+        let files = [
+            ("Cargo.toml", "[package]\nname = \"my-tool\"\n"),
+            (
+                "src/lib.rs",
+                "pub mod algo;\npub mod import;\npub struct Graph;\n#[cfg(test)]\nmod tests { use crate::algo; }\n",
+            ),
+            ("src/algo.rs", "use crate::Graph;\nuse std::collections::HashMap;\n"),
+            (
+                "src/import/mod.rs",
+                "pub mod cargo;\nmod inline {\n    use super::cargo::parse;\n}\n",
+            ),
+            (
+                "src/import/cargo.rs",
+                "use crate::{algo::{self, cycles}, Graph};\nfn parse() { use super::super::algo::*; }\n",
+            ),
+            (
+                "src/main.rs",
+                "mod cli;\nuse my_tool::{algo, import::cargo};\nuse cli::Args;\n",
+            ),
+            ("src/cli.rs", "use crate::Args;\n"),
+        ];
+        for (file, content) in files {
+            fs::write(root.join(file), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_scan() {
+        let dir = fixture();
+        let graph = scan(dir.path()).unwrap();
+        assert_eq!(
+            graph.nodes().map(|(_, name)| name).collect::<Vec<_>>(),
+            vec![
+                "my_tool",
+                "my_tool.algo",
+                "my_tool.import",
+                "my_tool.import.cargo",
+                "my_tool.import.inline",
+                "main",
+                "main.cli",
+            ]
+        );
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("my_tool.algo", "my_tool"),
+                ("my_tool.import.cargo", "my_tool.algo"),
+                ("my_tool.import.cargo", "my_tool"),
+                ("my_tool.import.inline", "my_tool.import.cargo"),
+                ("main", "my_tool.algo"),
+                ("main", "my_tool.import.cargo"),
+                ("main", "main.cli"),
+                ("main.cli", "main"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_writes_edge_list() {
+        let dir = fixture();
+        let graph = scan(dir.path()).unwrap();
+        let options = import::ImportOptions::default();
+        let mut output = Vec::new();
+        import::edges::write(
+            &graph,
+            &options.edge_delimiter,
+            &options.label_delimiter,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let parsed =
+            import::edges::parse(&output, &options.edge_delimiter, &options.label_delimiter)
+                .unwrap();
+        let nodes = |graph: &Graph| {
+            let mut nodes = graph
+                .nodes()
+                .map(|(_, name)| name.to_string())
+                .collect::<Vec<_>>();
+            nodes.sort();
+            nodes
+        };
+        assert_eq!(nodes(&parsed), nodes(&graph));
+        assert_eq!(edges(&parsed), edges(&graph));
+    }
+}